@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `blocks`, `offsets` and `chunks` helpers
+
+use handlebars::*;
+
+use crate::each;
+
+/// Parses a byte size such as `512B`, `4KiB` or `1 MB`
+///
+/// A size is a whole number followed by an optional unit: bytes (`B`), decimal
+/// multiples (`kB`, `MB`, `GB`, `TB`) or binary multiples (`KiB`, `MiB`,
+/// `GiB`, `TiB`). The case of the unit is ignored.
+fn size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let unit: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return None,
+    };
+
+    number.parse::<u64>().ok()?.checked_mul(unit)
+}
+
+/// Interprets a value as a byte size, either a number or a string with a unit
+fn value(value: &JsonValue) -> Option<u64> {
+    match value {
+        JsonValue::String(s) => size(s),
+        value => value.as_u64(),
+    }
+}
+
+/// Parses the named hash parameter of the named helper as a byte size
+fn hash(
+    h: &Helper<'_>,
+    helper: &'static str,
+    name: &'static str,
+) -> Result<Option<u64>, RenderError> {
+    match h.hash_get(name) {
+        None => Ok(None),
+        Some(size) => match value(size.value()) {
+            Some(size) => Ok(Some(size)),
+            None => Err(RenderErrorReason::HashTypeMismatchForName(
+                helper,
+                name.to_string(),
+                "byte size".to_string(),
+            )
+            .into()),
+        },
+    }
+}
+
+/// The `blocks` handler object
+///
+/// `{{#blocks "4KiB" block="512B"}}` renders its block once for each block of
+/// the `block` size within the size of its parameter. The sizes are numbers of
+/// bytes or strings giving a whole number and a unit, such as `512B`, `64 kB`
+/// or `4KiB`, where the decimal units are powers of 1000 and the binary units,
+/// such as `KiB`, powers of 1024.
+///
+/// Each repetition is rendered with the offset of its block as the context,
+/// which is also the `@offset` local variable and the block parameter, if any.
+/// The `@len` local variable holds the length of the block, which is shorter
+/// than the `block` size for a final, partial block. The `@index`, `@first` and
+/// `@last` local variables are also available. An empty size renders the
+/// inverse block. To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("blocks", Box::new(handlebars_repeat::BlocksHelper::default()));
+///
+/// let template = "{{#blocks \"1300B\" block=\"512B\"}}{{@offset}}+{{@len}} {{/blocks}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "0+512 512+512 1024+276 ");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BlocksHelper {
+    max_count: Option<u64>,
+}
+
+impl BlocksHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+impl HelperDef for BlocksHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let total = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("blocks", 0))?;
+        let total = value(total.value()).ok_or_else(|| {
+            RenderErrorReason::ParamTypeMismatchForName(
+                "blocks",
+                "0".to_string(),
+                "byte size".to_string(),
+            )
+        })?;
+
+        let size = hash(h, "blocks", "block")?.ok_or_else(|| {
+            RenderErrorReason::ParamNotFoundForName("blocks", "block".to_string())
+        })?;
+        if size == 0 {
+            return Err(RenderErrorReason::HashTypeMismatchForName(
+                "blocks",
+                "block".to_string(),
+                "non-zero byte size".to_string(),
+            )
+            .into());
+        }
+
+        self.render(total, size, None, h, r, ctx, rc, out)
+    }
+}
+
+impl BlocksHelper {
+    /// Renders the blocks of the size within the total, with the offsets in
+    /// hexadecimal of the given number of digits, if any
+    #[allow(clippy::too_many_arguments)]
+    fn render<'reg: 'rc, 'rc>(
+        &self,
+        total: u64,
+        size: u64,
+        hex: Option<usize>,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let count = total / size + u64::from(total % size != 0);
+
+        let items = (0..count).map(|n| {
+            let offset = n * size;
+
+            let mut item = each::Item::new(offset);
+            item.locals.set(
+                "offset",
+                match hex {
+                    Some(digits) => format!("0x{:0digits$x}", offset, digits = digits).into(),
+                    None => JsonValue::from(offset),
+                },
+            );
+            item.locals.set("len", size.min(total - offset));
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
+
+/// The most hexadecimal digits of an offset, as many as a `u64` has
+const MAX_DIGITS: u64 = 16;
+
+/// The `offsets` handler object
+///
+/// `{{#offsets total=256 width=16}}` renders its block once for each row of
+/// `width` bytes within the `total` size, as the `blocks` helper does (see
+/// [`BlocksHelper`]) for each block, but with the `@offset` local variable in
+/// hexadecimal, such as `0x00`, `0x10` and so on. The sizes are numbers of
+/// bytes or strings with units, such as `4KiB`, and the `width` is 16 by
+/// default. The offsets have as many digits as the greatest needs, or at least
+/// the number of the `digits` hash parameter, which is at most 16, the digits
+/// of the greatest 64-bit offset. The context remains the offset
+/// as a number. To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("offsets", Box::new(handlebars_repeat::OffsetsHelper::default()));
+///
+/// let template = "{{#offsets total=64 width=16 digits=8}}{{@offset}}: ...\n{{/offsets}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "0x00000000: ...\n0x00000010: ...\n0x00000020: ...\n0x00000030: ...\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OffsetsHelper(BlocksHelper);
+
+impl From<BlocksHelper> for OffsetsHelper {
+    fn from(helper: BlocksHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl HelperDef for OffsetsHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let total = hash(h, "offsets", "total")?.ok_or_else(|| {
+            RenderErrorReason::ParamNotFoundForName("offsets", "total".to_string())
+        })?;
+
+        let width = match hash(h, "offsets", "width")? {
+            None => 16,
+            Some(width) if width > 0 => width,
+            Some(..) => {
+                return Err(RenderErrorReason::HashTypeMismatchForName(
+                    "offsets",
+                    "width".to_string(),
+                    "non-zero byte size".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let digits = match h.hash_get("digits") {
+            None => 0,
+            Some(digits) => digits
+                .value()
+                .as_u64()
+                .filter(|digits| *digits <= MAX_DIGITS)
+                .ok_or_else(|| {
+                    RenderErrorReason::HashTypeMismatchForName(
+                        "offsets",
+                        "digits".to_string(),
+                        "at most 16 digits".to_string(),
+                    )
+                })? as usize,
+        };
+
+        // The greatest offset is that of the last row.
+        let greatest = total.saturating_sub(1) / width * width;
+        let needed = format!("{:02x}", greatest).len();
+        self.0
+            .render(total, width, Some(needed.max(digits)), h, r, ctx, rc, out)
+    }
+}
+
+/// The `chunks` handler object
+///
+/// `{{#chunks total=1000 size=256}}` renders its block once for each chunk of
+/// `size` within the `total`, as the `blocks` helper does (see
+/// [`BlocksHelper`]) for each block. The `@offset` and `@len` local variables
+/// describe the chunk, so that the final chunk, which is short when the total
+/// is not a multiple of the size, needs no arithmetic in the template. To use,
+/// register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("chunks", Box::new(handlebars_repeat::ChunksHelper::default()));
+///
+/// let template = "{{#chunks total=1000 size=256}}[{{@offset}}, {{@len}}]{{#unless @last}} {{/unless}}{{/chunks}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "[0, 256] [256, 256] [512, 256] [768, 232]"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChunksHelper(BlocksHelper);
+
+impl From<BlocksHelper> for ChunksHelper {
+    fn from(helper: BlocksHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl HelperDef for ChunksHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let total = hash(h, "chunks", "total")?.ok_or_else(|| {
+            RenderErrorReason::ParamNotFoundForName("chunks", "total".to_string())
+        })?;
+
+        let size = match hash(h, "chunks", "size")? {
+            Some(size) if size > 0 => size,
+            Some(..) => {
+                return Err(RenderErrorReason::HashTypeMismatchForName(
+                    "chunks",
+                    "size".to_string(),
+                    "non-zero u64".to_string(),
+                )
+                .into())
+            }
+            None => {
+                return Err(
+                    RenderErrorReason::ParamNotFoundForName("chunks", "size".to_string()).into(),
+                )
+            }
+        };
+
+        self.0.render(total, size, None, h, r, ctx, rc, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("512", Some(512))]
+    #[case("512B", Some(512))]
+    #[case("4KiB", Some(4096))]
+    #[case("4 kib", Some(4096))]
+    #[case("64kB", Some(64_000))]
+    #[case("2MiB", Some(2 << 20))]
+    #[case("1GB", Some(1_000_000_000))]
+    #[case("1TiB", Some(1 << 40))]
+    #[case("", None)]
+    #[case("KiB", None)]
+    #[case("1.5MiB", None)]
+    #[case("4K", None)]
+    #[case("-1B", None)]
+    #[case("99999999TiB", None)]
+    fn sizes(#[case] s: &str, #[case] bytes: Option<u64>) {
+        assert_eq!(size(s), bytes);
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `calendar` helper
+
+use handlebars::*;
+
+use crate::date::{days_in_month, Date, WEEKDAYS};
+use crate::each;
+
+/// The `calendar` handler object
+///
+/// `{{#calendar year=2025 month=6}}` renders its block once for each day in
+/// the grid of a month: the whole weeks, from Monday to Sunday, which contain
+/// its days. Setting the `week_start` hash parameter to `"sunday"` begins the
+/// weeks on Sundays instead.
+///
+/// Each repetition is rendered with its date, in the `YYYY-MM-DD` form, as the
+/// context and the block parameter, if any. The `@day` local variable holds
+/// the day of the month, `@in_month` whether the day belongs to the month
+/// rather than a neighbouring one and `@weekday` the English name of the day of
+/// the week. The `@week` local variable counts the weeks of the grid from zero,
+/// and `@week_start` and `@week_end` mark their boundaries, along with
+/// `@index`, `@first` and `@last`. To use, register it in your handlebars
+/// registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("calendar", Box::new(handlebars_repeat::CalendarHelper::default()));
+///
+/// let template = "{{#calendar year=2025 month=2}}{{#if @in_month}}{{@day}}{{else}}-{{/if}}{{#if @week_end}}\n{{else}} {{/if}}{{/calendar}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "- - - - - 1 2\n3 4 5 6 7 8 9\n10 11 12 13 14 15 16\n17 18 19 20 21 22 23\n24 25 26 27 28 - -\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CalendarHelper {
+    max_count: Option<u64>,
+}
+
+impl CalendarHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// Parses the named hash parameter as a number within the range
+fn number(
+    h: &Helper<'_>,
+    name: &'static str,
+    range: std::ops::RangeInclusive<i64>,
+) -> Result<i64, RenderError> {
+    let value = h
+        .hash_get(name)
+        .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("calendar", name.to_string()))?;
+
+    value
+        .value()
+        .as_i64()
+        .filter(|n| range.contains(n))
+        .ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "calendar",
+                name.to_string(),
+                name.to_string(),
+            )
+            .into()
+        })
+}
+
+impl HelperDef for CalendarHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let year = number(h, "year", 1..=9999)?;
+        let month = number(h, "month", 1..=12)? as u32;
+
+        // The weekday, from zero for Monday, on which the weeks begin
+        let week_start = match h.hash_get("week_start").map(|v| v.value().as_str()) {
+            None | Some(Some("monday")) => 0,
+            Some(Some("sunday")) => 6,
+            Some(..) => {
+                return Err(RenderErrorReason::HashTypeMismatchForName(
+                    "calendar",
+                    "week_start".to_string(),
+                    "\"monday\" or \"sunday\"".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let first = Date {
+            year,
+            month,
+            day: 1,
+        };
+        let lead = (first.weekday() + 7 - week_start) % 7;
+        let start = first.add_days(-i64::from(lead));
+        let weeks = (lead + days_in_month(year, month) + 6) / 7;
+        let count = weeks * 7;
+
+        let items = (0..count).map(|n| {
+            let date = start.add_days(i64::from(n));
+
+            let mut item = each::Item::new(date.format("%Y-%m-%d").unwrap_or_default());
+            item.locals.set("day", date.day);
+            item.locals.set("in_month", date.month == month);
+            item.locals
+                .set("weekday", WEEKDAYS[date.weekday() as usize]);
+            item.locals.set("week", n / 7);
+            item.locals.set("week_start", n % 7 == 0);
+            item.locals.set("week_end", n % 7 == 6);
+            item
+        });
+
+        each::render(self.max_count, count.into(), items, h, r, ctx, rc, out)
+    }
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `chars` and `alphabet` helpers
+
+use handlebars::*;
+
+use crate::each;
+
+/// The `chars` handler object
+///
+/// `{{#chars "a" "z"}}` renders its block once for each Unicode scalar value
+/// from the first bound up to and including the second, which are strings of
+/// a single character. When the first bound is the greater, the characters
+/// count down instead. Each repetition is rendered with its character as the
+/// context, which is also the `@char` local variable and the block parameter,
+/// if any, along with `@index`, `@first` and `@last`. To use, register it in
+/// your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("chars", Box::new(handlebars_repeat::CharsHelper::default()));
+///
+/// let template = "{{#chars \"C\" \"F\"}}{{@char}}:{{#if @last}}{{else}} {{/if}}{{/chars}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "C: D: E: F:");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CharsHelper {
+    max_count: Option<u64>,
+}
+
+impl CharsHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+
+    /// Renders the characters from `first` to `last`, inclusive
+    #[allow(clippy::too_many_arguments)]
+    fn render<'reg: 'rc, 'rc>(
+        &self,
+        first: char,
+        last: char,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let (lo, hi) = (first.min(last), first.max(last));
+
+        // The bounds are scalar values, so the surrogates lie wholly within
+        // them or wholly without.
+        let mut count = u64::from(hi as u32 - lo as u32) + 1;
+        if (lo as u32) < 0xD800 && (hi as u32) > 0xDFFF {
+            count -= 0x800;
+        }
+
+        let chars: Box<dyn Iterator<Item = char>> = match first <= last {
+            true => Box::new(lo..=hi),
+            false => Box::new((lo..=hi).rev()),
+        };
+
+        let items = chars.map(|c| {
+            let mut item = each::Item::new(c.to_string());
+            item.locals.set("char", c.to_string());
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
+
+fn param(h: &Helper<'_>, index: usize) -> Result<char, RenderError> {
+    let value = h
+        .param(index)
+        .ok_or(RenderErrorReason::ParamNotFoundForIndex("chars", index))?;
+
+    let mut chars = value.value().as_str().unwrap_or_default().chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(RenderErrorReason::ParamTypeMismatchForName(
+            "chars",
+            index.to_string(),
+            "char".to_string(),
+        )
+        .into()),
+    }
+}
+
+impl HelperDef for CharsHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        self.render(param(h, 0)?, param(h, 1)?, h, r, ctx, rc, out)
+    }
+}
+
+/// The `alphabet` handler object
+///
+/// `{{#alphabet}}` renders its block for each of the letters from `a` to `z`,
+/// exactly as `{{#chars "a" "z"}}` does (see [`CharsHelper`]). Setting the
+/// `upper` hash parameter to `true` uses the letters from `A` to `Z` instead.
+/// To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("alphabet", Box::new(handlebars_repeat::AlphabetHelper::default()));
+///
+/// let template = "{{#alphabet upper=true}}{{#if (lt @index 3)}}{{this}}{{/if}}{{/alphabet}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "ABC");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AlphabetHelper(CharsHelper);
+
+impl From<CharsHelper> for AlphabetHelper {
+    fn from(helper: CharsHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl HelperDef for AlphabetHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let upper = h
+            .hash_get("upper")
+            .map_or(false, |upper| upper.value().is_truthy(false));
+
+        match upper {
+            true => self.0.render('A', 'Z', h, r, ctx, rc, out),
+            false => self.0.render('a', 'z', h, r, ctx, rc, out),
+        }
+    }
+}
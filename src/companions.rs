@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers which act upon the enclosing `repeat` block
+
+use std::fmt;
+
+use handlebars::*;
+
+use crate::{frames, shared::Shared, Error};
+
+/// A signal to the enclosing `repeat` block, unwinding the repetition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Signal {
+    Break,
+    Continue,
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Break => write!(f, "break"),
+            Self::Continue => write!(f, "continue"),
+        }
+    }
+}
+
+impl std::error::Error for Signal {}
+
+impl Signal {
+    /// Raises the signal, provided it is within a `repeat` block
+    fn raise(self, name: &'static str, rc: &RenderContext<'_, '_>) -> HelperResult {
+        if !frames::within(rc) {
+            return Err(Error::OutsideRepeat(name).into());
+        }
+
+        Err(RenderErrorReason::NestedError(Box::new(self)).into())
+    }
+}
+
+/// Catches a signal raised while rendering, recording it
+pub(crate) fn catch(result: HelperResult, signal: &mut Option<Signal>) -> HelperResult {
+    match result {
+        Err(err) => match err.reason() {
+            RenderErrorReason::NestedError(e) if e.is::<Signal>() => {
+                *signal = e.downcast_ref::<Signal>().copied();
+                Ok(())
+            }
+
+            _ => Err(err),
+        },
+
+        Ok(()) => Ok(()),
+    }
+}
+
+/// The `sep` handler object
+///
+/// Within a `repeat` block, it renders its own block on every repetition but
+/// the last, permitting separators with expressions and line breaks. To use,
+/// register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper::default()));
+/// reg.register_helper("sep", Box::new(handlebars_repeat::SepHelper));
+///
+/// let template = "{{#repeat 3}}{{@index}}{{#sep}} <{{@index}}> {{/sep}}{{/repeat}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "0 <0> 1 <1> 2");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SepHelper;
+
+impl HelperDef for SepHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        if !frames::within(rc) {
+            return Err(Error::OutsideRepeat("sep").into());
+        }
+
+        if frames::last(rc) == Some(false) {
+            if let Some(template) = h.template() {
+                template.render(r, ctx, rc, out)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The `break` handler object
+///
+/// Within a `repeat` block, it abandons the rest of the repetition and renders
+/// no more of them. To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper::default()));
+/// reg.register_helper("break", Box::new(handlebars_repeat::BreakHelper));
+///
+/// let template = "{{#repeat 5}}{{@index}}{{#if @index}}{{break}}{{/if}};{{/repeat}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "0;1");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BreakHelper;
+
+impl HelperDef for BreakHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        _: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        _: &mut dyn Output,
+    ) -> HelperResult {
+        Signal::Break.raise("break", rc)
+    }
+}
+
+/// The `continue` handler object
+///
+/// Within a `repeat` block, it abandons the rest of the repetition and moves
+/// on to the next. To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper::default()));
+/// reg.register_helper("continue", Box::new(handlebars_repeat::ContinueHelper));
+///
+/// let template = "{{#repeat 3}}{{@index}}{{#if @first}}{{continue}}{{/if}};{{/repeat}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "01;2;");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContinueHelper;
+
+impl HelperDef for ContinueHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        _: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        _: &mut dyn Output,
+    ) -> HelperResult {
+        Signal::Continue.raise("continue", rc)
+    }
+}
+
+/// Adds the value to an accumulator, summing numbers or collecting any value
+fn add(accumulator: &mut JsonValue, value: JsonValue, collect: bool) -> HelperResult {
+    if collect {
+        match accumulator {
+            JsonValue::Array(values) => values.push(value),
+            _ => *accumulator = JsonValue::Array(vec![value]),
+        }
+
+        return Ok(());
+    }
+
+    *accumulator = match (&*accumulator, &value) {
+        (JsonValue::Null, JsonValue::Number(..)) => value,
+        (JsonValue::Number(a), JsonValue::Number(b)) => {
+            let sum = a.as_i64().zip(b.as_i64());
+            match sum.and_then(|(a, b)| a.checked_add(b)) {
+                Some(sum) => sum.into(),
+                None => (a.as_f64().unwrap_or(0.0) + b.as_f64().unwrap_or(0.0)).into(),
+            }
+        }
+
+        _ => {
+            return Err(RenderErrorReason::ParamTypeMismatchForName(
+                "acc",
+                "1".to_string(),
+                "number".to_string(),
+            )
+            .into())
+        }
+    };
+
+    Ok(())
+}
+
+/// The `acc` handler object
+///
+/// Within a `repeat` block, `{{acc "total" price}}` adds the value to the
+/// named accumulator of the block, writing nothing. Numbers are summed, while
+/// with `collect=true` values of any kind are collected into an array. Given
+/// no value, `{{acc "total"}}` writes the accumulator: its value so far within
+/// the block, or its final value after it. To use, register it in your
+/// handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper::default()));
+/// reg.register_helper("acc", Box::new(handlebars_repeat::AccHelper));
+///
+/// let template = "{{#repeat 4}}{{acc \"total\" @index}}{{/repeat}}total: {{acc \"total\"}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "total: 6");
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccHelper;
+
+impl HelperDef for AccHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let name = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("acc", 0))?;
+        let name = name.value().as_str().ok_or_else(|| {
+            RenderErrorReason::ParamTypeMismatchForName(
+                "acc",
+                "0".to_string(),
+                "string".to_string(),
+            )
+        })?;
+
+        let shared = Shared::of(h, r, ctx, rc);
+        if let Some(value) = h.param(1) {
+            let collect = h.hash_get("collect").and_then(|v| v.value().as_bool());
+            let value = value.value().clone();
+            return frames::accumulators(&shared, rc, |a| {
+                add(&mut a[name], value, collect == Some(true))
+            })
+            .ok_or(Error::OutsideRepeat("acc"))?;
+        }
+
+        let value = frames::accumulators(&shared, rc, |a| a.get(name).cloned()).flatten();
+        if let Some(value) = value.or_else(|| shared.published(name)) {
+            out.write(&value.render())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `while` helper
+
+use handlebars::*;
+
+use crate::expression::Expression;
+use crate::{companions, frames, shared::Shared, Error, RepeatHelper};
+
+/// The `while` handler object
+///
+/// `{{#while "condition" max=N}}` renders its block for as long as the
+/// condition holds, up to the mandatory `max` repetitions, which is a number
+/// as for the count of `repeat`. The condition is evaluated again for each
+/// repetition, within the block context of that repetition, so it may depend
+/// upon the `@index` and `@first` local variables as well as the data. It is
+/// evaluated for the next repetition before the current one is rendered, so
+/// that `@last` is true when it no longer holds. The block parameter, if any,
+/// is the index. When the condition does not hold initially, the inverse block
+/// is rendered instead.
+///
+/// The `break`, `continue`, `sep` and `acc` helpers act upon the `while`
+/// block as they do upon a `repeat` block.
+///
+/// As for the `until` hash parameter of `repeat`, the condition is a path or a
+/// string holding a path or a subexpression, such as `"lt @index 3"`, since
+/// Handlebars evaluates any other parameter once, before the block. Any other
+/// value, such as `true`, is a condition which never changes. To use, register
+/// it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("while", Box::new(handlebars_repeat::WhileHelper::default()));
+///
+/// let template = "{{#while \"lookup queue @index\" max=100}}{{lookup queue @index}} {{/while}}";
+/// let data = serde_json::json!({ "queue": ["a", "b", "c"] });
+/// assert_eq!(reg.render_template(template, &data).unwrap(), "a b c ");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WhileHelper {
+    max_count: Option<u64>,
+}
+
+impl WhileHelper {
+    /// Sets a maximum number of repetitions, which `max` may not exceed
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+impl HelperDef for WhileHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let condition = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("while", 0))?;
+        let constant = condition.value().is_truthy(false);
+        let condition = Expression::new(condition);
+
+        let max = h
+            .hash_get("max")
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("while", "max".to_string()))?;
+        let max = RepeatHelper::default().number(max.value()).ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "while",
+                "max".to_string(),
+                "u64".to_string(),
+            )
+        })?;
+
+        if let Some(limit) = self.max_count.filter(|limit| max > *limit) {
+            return Err(Error::TooManyRepetitions {
+                count: max,
+                max: limit,
+            }
+            .into());
+        }
+
+        let template = h
+            .template()
+            .ok_or(RenderErrorReason::BlockContentRequired)?;
+
+        let shared = Shared::of(h, r, ctx, rc);
+        let frame = frames::Frame::new(&shared, rc, None);
+        let mut signal = None;
+        let mut count = 0;
+
+        // Whether a repetition is the last is only known once the condition
+        // of the next has been evaluated.
+        let mut holds = max > 0 && check(h, &condition, constant, 0, r, ctx, rc)?;
+        while holds {
+            let next = count + 1 < max && check(h, &condition, constant, count + 1, r, ctx, rc)?;
+
+            let mut block = block(h, count, rc)?;
+            let mut locals = JsonValue::Null;
+            locals["index"] = count.into();
+            locals["first"] = (count == 0).into();
+            locals["last"] = (!next).into();
+
+            block.set_local_var("last", (!next).into());
+            frame.set(&mut block, Some(!next), Some(locals));
+
+            rc.push_block(block);
+            let result = companions::catch(template.render(r, ctx, rc, out), &mut signal);
+            rc.pop_block();
+            result?;
+
+            count += 1;
+            if signal == Some(companions::Signal::Break) {
+                break;
+            }
+
+            holds = next;
+        }
+
+        frame.end();
+
+        if count == 0 {
+            if let Some(template) = h.inverse() {
+                template.render(r, ctx, rc, out)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the block of the repetition rendered `n`th, with its index and
+/// whether it is the first
+fn block<'rc>(
+    h: &Helper<'rc>,
+    n: u64,
+    rc: &RenderContext<'_, 'rc>,
+) -> Result<BlockContext<'rc>, RenderError> {
+    let mut block = rc.block().cloned().unwrap_or_default();
+
+    if let Some(name) = h.block_param() {
+        let mut params = BlockParams::new();
+        params.add_value(name, n.into())?;
+        block.set_block_params(params);
+    }
+
+    block.set_local_var("index", n.into());
+    block.set_local_var("first", (n == 0).into());
+    Ok(block)
+}
+
+/// Evaluates the condition within the block of the repetition rendered `n`th
+#[allow(clippy::too_many_arguments)]
+fn check<'reg: 'rc, 'rc>(
+    h: &Helper<'rc>,
+    condition: &Option<Expression>,
+    constant: bool,
+    n: u64,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+) -> Result<bool, RenderError> {
+    let condition = match condition {
+        Some(condition) => condition,
+        None => return Ok(constant),
+    };
+
+    rc.push_block(block(h, n, rc)?);
+    let holds = condition.holds(r, ctx, rc);
+    rc.pop_block();
+    holds
+}
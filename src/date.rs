@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proleptic Gregorian calendar arithmetic and formatting
+
+use std::fmt::Write;
+
+/// The English names of the days of the week, from Monday
+pub(crate) const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// The English names of the months, from January
+pub(crate) const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// The number of days in the month of the year
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A calendar date
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Date {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+}
+
+impl Date {
+    /// Creates a date, provided the day exists
+    pub(crate) fn new(year: i64, month: u32, day: u32) -> Option<Self> {
+        match (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month) {
+            true => Some(Self { year, month, day }),
+            false => None,
+        }
+    }
+
+    /// Parses a date in the `YYYY-MM-DD` form
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.splitn(3, '-');
+        let year = number(parts.next()?, 4)?;
+        let month = number(parts.next()?, 2)?;
+        let day = number(parts.next()?, 2)?;
+        Self::new(year, month as u32, day as u32)
+    }
+
+    /// The date of the given number of days since 1970-01-01
+    pub(crate) fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+
+    /// The number of days since 1970-01-01
+    pub(crate) fn days(&self) -> i64 {
+        let year = self.year - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = i64::from((self.month + 9) % 12);
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The day of the week, from zero for Monday
+    pub(crate) fn weekday(&self) -> u32 {
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+
+    /// The day of the year, from one
+    pub(crate) fn ordinal(&self) -> u32 {
+        (1..self.month)
+            .map(|m| days_in_month(self.year, m))
+            .sum::<u32>()
+            + self.day
+    }
+
+    /// The Monday of the first ISO week of the year
+    pub(crate) fn first_week(year: i64) -> Self {
+        let jan4 = Self {
+            year,
+            month: 1,
+            day: 4,
+        };
+        jan4.add_days(-i64::from(jan4.weekday()))
+    }
+
+    /// The ISO week-numbering year and week of the date
+    pub(crate) fn iso_week(&self) -> (i64, u32) {
+        let monday = self.add_days(-i64::from(self.weekday()));
+        let thursday = monday.add_days(3);
+        let start = Self::first_week(thursday.year);
+        (
+            thursday.year,
+            ((monday.days() - start.days()) / 7) as u32 + 1,
+        )
+    }
+
+    /// Adds a number of days
+    pub(crate) fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.days() + days)
+    }
+
+    /// Adds a number of months, clamping the day to the end of the month
+    pub(crate) fn add_months(&self, months: i64) -> Self {
+        let months = self.year * 12 + i64::from(self.month) - 1 + months;
+        let year = months.div_euclid(12);
+        let month = months.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Self { year, month, day }
+    }
+
+    /// Formats the date with a `strftime`-style format
+    ///
+    /// The supported conversions are `%Y`, `%m`, `%d`, `%e`, `%j`, `%u`, `%a`,
+    /// `%A`, `%b`, `%B` and `%%`. Any other conversion is an error.
+    pub(crate) fn format(&self, format: &str) -> Option<String> {
+        let mut out = String::new();
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            let weekday = WEEKDAYS[self.weekday() as usize];
+            let month = MONTHS[self.month as usize - 1];
+            let _ = match chars.next()? {
+                'Y' => write!(out, "{:04}", self.year),
+                'm' => write!(out, "{:02}", self.month),
+                'd' => write!(out, "{:02}", self.day),
+                'e' => write!(out, "{:2}", self.day),
+                'j' => write!(out, "{:03}", self.ordinal()),
+                'u' => write!(out, "{}", self.weekday() + 1),
+                'a' => write!(out, "{}", &weekday[..3]),
+                'A' => write!(out, "{}", weekday),
+                'b' => write!(out, "{}", &month[..3]),
+                'B' => write!(out, "{}", month),
+                '%' => write!(out, "%"),
+                _ => return None,
+            };
+        }
+
+        Some(out)
+    }
+}
+
+/// Parses a number of exactly the given number of digits
+fn number(s: &str, digits: usize) -> Option<i64> {
+    match s.len() == digits && s.bytes().all(|b| b.is_ascii_digit()) {
+        true => s.parse().ok(),
+        false => None,
+    }
+}
+
+/// A step between dates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Step {
+    Days(i64),
+    Months(i64),
+}
+
+impl Step {
+    /// Parses a positive step such as `1d`, `2w`, `3m` or `1y`
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let unit = s.chars().last()?;
+        let n: i64 = s[..s.len() - unit.len_utf8()].parse().ok()?;
+        if n <= 0 {
+            return None;
+        }
+
+        match unit {
+            'd' => Some(Self::Days(n)),
+            'w' => n.checked_mul(7).map(Self::Days),
+            'm' => Some(Self::Months(n)),
+            'y' => n.checked_mul(12).map(Self::Months),
+            _ => None,
+        }
+    }
+
+    /// The date the given number of steps after the start
+    ///
+    /// Month steps are taken from the start, so that the day is not lost to
+    /// the clamping of shorter months.
+    pub(crate) fn nth(&self, start: &Date, n: i64) -> Date {
+        match *self {
+            Self::Days(days) => start.add_days(days.saturating_mul(n)),
+            Self::Months(months) => start.add_months(months.saturating_mul(n)),
+        }
+    }
+
+    /// The number of steps from the start up to and including the end
+    pub(crate) fn count(&self, start: &Date, end: &Date) -> u64 {
+        if end < start {
+            return 0;
+        }
+
+        let steps = match *self {
+            Self::Days(days) => (end.days() - start.days()) / days,
+            Self::Months(months) => {
+                let span =
+                    (end.year - start.year) * 12 + i64::from(end.month) - i64::from(start.month);
+                let steps = span / months;
+                steps - i64::from(self.nth(start, steps) > *end)
+            }
+        };
+
+        steps as u64 + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days() {
+        for (date, days, weekday) in [
+            ("1970-01-01", 0, 3),
+            ("2000-02-29", 11016, 1),
+            ("2024-01-01", 19723, 0),
+            ("1969-12-31", -1, 2),
+            ("0001-01-01", -719_162, 0),
+        ] {
+            let date = Date::parse(date).unwrap();
+            assert_eq!(date.days(), days);
+            assert_eq!(Date::from_days(days), date);
+            assert_eq!(date.weekday(), weekday);
+        }
+    }
+
+    #[test]
+    fn weeks() {
+        for (date, year, week) in [
+            ("2024-01-01", 2024, 1),
+            ("2024-12-29", 2024, 52),
+            ("2024-12-30", 2025, 1),
+            ("2021-01-03", 2020, 53),
+            ("2026-12-31", 2026, 53),
+            ("2027-01-04", 2027, 1),
+        ] {
+            assert_eq!(Date::parse(date).unwrap().iso_week(), (year, week));
+        }
+
+        assert_eq!(Date::first_week(2025), Date::new(2024, 12, 30).unwrap());
+        assert_eq!(Date::first_week(2021), Date::new(2021, 1, 4).unwrap());
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Date::parse("2024-02-29"), Date::new(2024, 2, 29));
+        assert_eq!(Date::parse("2023-02-29"), None);
+        assert_eq!(Date::parse("2024-13-01"), None);
+        assert_eq!(Date::parse("2024-1-01"), None);
+        assert_eq!(Date::parse("2024-01-01x"), None);
+    }
+
+    #[test]
+    fn months() {
+        let date = Date::new(2024, 1, 31).unwrap();
+        assert_eq!(date.add_months(1), Date::new(2024, 2, 29).unwrap());
+        assert_eq!(date.add_months(-2), Date::new(2023, 11, 30).unwrap());
+        assert_eq!(
+            Step::Months(1).nth(&date, 2),
+            Date::new(2024, 3, 31).unwrap()
+        );
+    }
+
+    #[test]
+    fn steps() {
+        assert_eq!(Step::parse("1d"), Some(Step::Days(1)));
+        assert_eq!(Step::parse("2w"), Some(Step::Days(14)));
+        assert_eq!(Step::parse("3m"), Some(Step::Months(3)));
+        assert_eq!(Step::parse("1y"), Some(Step::Months(12)));
+        assert_eq!(Step::parse("0d"), None);
+        assert_eq!(Step::parse("d"), None);
+        assert_eq!(Step::parse("1h"), None);
+
+        let start = Date::new(2024, 1, 31).unwrap();
+        let end = Date::new(2024, 4, 30).unwrap();
+        assert_eq!(Step::Days(1).count(&start, &end), 91);
+        assert_eq!(Step::Days(7).count(&start, &end), 13);
+        assert_eq!(Step::Months(1).count(&start, &end), 4);
+        assert_eq!(Step::Months(1).count(&end, &start), 0);
+        assert_eq!(Step::Months(12).count(&start, &start), 1);
+    }
+
+    #[test]
+    fn format() {
+        let date = Date::new(2024, 3, 5).unwrap();
+        let format = |f| date.format(f);
+        assert_eq!(format("%Y-%m-%d").unwrap(), "2024-03-05");
+        assert_eq!(
+            format("%a %e %b, day %j (%u)").unwrap(),
+            "Tue  5 Mar, day 065 (2)"
+        );
+        assert_eq!(format("%A %d %B 100%%").unwrap(), "Tuesday 05 March 100%");
+        assert_eq!(format("%q"), None);
+        assert_eq!(format("%"), None);
+    }
+}
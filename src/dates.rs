@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `dates` and `business_days` helpers
+
+use handlebars::*;
+
+use crate::date::{Date, Step, WEEKDAYS};
+use crate::each;
+
+/// The `dates` handler object
+///
+/// `{{#dates from="2024-01-01" to="2024-01-31"}}` renders its block once for
+/// each calendar day from the `from` date up to and including the `to` date,
+/// both in the `YYYY-MM-DD` form. The `step` hash parameter gives a different
+/// step as a count of days (`d`), weeks (`w`), months (`m`) or years (`y`),
+/// such as `2w`; month steps keep the day of the `from` date where the month
+/// has it and otherwise use the last day of the month.
+///
+/// Each repetition is rendered with its date as the context, which is also the
+/// `@date` local variable and the block parameter, if any. It is formatted by
+/// the `format` hash parameter, a `strftime`-style format supporting `%Y`,
+/// `%m`, `%d`, `%e`, `%j`, `%u`, `%a`, `%A`, `%b`, `%B` and `%%`, which is
+/// `%Y-%m-%d` by default. The `@weekday` local variable holds the English name
+/// of the day of the week, along with `@index`, `@first` and `@last`. When no
+/// dates are in range, the inverse block is rendered. To use, register it in
+/// your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("dates", Box::new(handlebars_repeat::DatesHelper::default()));
+///
+/// let template = "{{#dates from=\"2024-02-27\" to=\"2024-03-01\" format=\"%d %b\"}}{{this}} ({{@weekday}})\n{{/dates}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "27 Feb (Tuesday)\n28 Feb (Wednesday)\n29 Feb (Thursday)\n01 Mar (Friday)\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DatesHelper {
+    max_count: Option<u64>,
+}
+
+impl DatesHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// Parses the named hash parameter of the named helper as a date
+fn date(h: &Helper<'_>, helper: &'static str, name: &'static str) -> Result<Date, RenderError> {
+    let value = h
+        .hash_get(name)
+        .ok_or_else(|| RenderErrorReason::ParamNotFoundForName(helper, name.to_string()))?;
+
+    value.value().as_str().and_then(Date::parse).ok_or_else(|| {
+        RenderErrorReason::HashTypeMismatchForName(helper, name.to_string(), "date".to_string())
+            .into()
+    })
+}
+
+impl HelperDef for DatesHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let step = match h.hash_get("step") {
+            None => Step::Days(1),
+            Some(step) => step.value().as_str().and_then(Step::parse).ok_or_else(|| {
+                RenderErrorReason::HashTypeMismatchForName(
+                    "dates",
+                    "step".to_string(),
+                    "step such as 1d".to_string(),
+                )
+            })?,
+        };
+
+        self.render("dates", step, |_| false, h, r, ctx, rc, out)
+    }
+}
+
+impl DatesHelper {
+    /// Renders the dates of the range by the step, skipping some of them
+    #[allow(clippy::too_many_arguments)]
+    fn render<'reg: 'rc, 'rc>(
+        &self,
+        helper: &'static str,
+        step: Step,
+        skip: impl Fn(&Date) -> bool,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let from = date(h, helper, "from")?;
+        let to = date(h, helper, "to")?;
+
+        let format = match h.hash_get("format") {
+            None => "%Y-%m-%d",
+            Some(format) => match format.value().as_str() {
+                Some(format) if from.format(format).is_some() => format,
+                _ => {
+                    return Err(RenderErrorReason::HashTypeMismatchForName(
+                        helper,
+                        "format".to_string(),
+                        "strftime-style format".to_string(),
+                    )
+                    .into())
+                }
+            },
+        };
+
+        let dates = || {
+            (0..step.count(&from, &to))
+                .map(|n| step.nth(&from, n as i64))
+                .filter(|date| !skip(date))
+        };
+
+        let count = dates().count() as u64;
+
+        let items = dates().map(|date| {
+            let formatted = date.format(format).unwrap_or_default();
+
+            let mut item = each::Item::new(formatted.clone());
+            item.locals.set("date", formatted);
+            item.locals
+                .set("weekday", WEEKDAYS[date.weekday() as usize]);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
+
+/// The `business_days` handler object
+///
+/// `{{#business_days from="2024-01-01" to="2024-01-31"}}` renders its block
+/// for each day from Monday to Friday within the range, as the `dates` helper
+/// does (see [`DatesHelper`]) for every day. The `holidays` hash parameter, an
+/// array of dates in the `YYYY-MM-DD` form, gives further days to skip. The
+/// `@index`, `@first` and `@last` local variables count the business days
+/// alone. To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("business_days", Box::new(handlebars_repeat::BusinessDaysHelper::default()));
+///
+/// let template = "{{#business_days from=\"2024-12-23\" to=\"2024-12-31\" holidays=holidays}}{{@weekday}} {{this}}\n{{/business_days}}";
+/// let data = serde_json::json!({ "holidays": ["2024-12-25", "2024-12-26"] });
+/// assert_eq!(
+///     reg.render_template(template, &data).unwrap(),
+///     "Monday 2024-12-23\nTuesday 2024-12-24\nFriday 2024-12-27\nMonday 2024-12-30\nTuesday 2024-12-31\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BusinessDaysHelper(DatesHelper);
+
+impl From<DatesHelper> for BusinessDaysHelper {
+    fn from(helper: DatesHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl HelperDef for BusinessDaysHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let holidays = match h.hash_get("holidays") {
+            None => Vec::new(),
+            Some(holidays) => holidays
+                .value()
+                .as_array()
+                .and_then(|a| a.iter().map(|d| d.as_str().and_then(Date::parse)).collect())
+                .ok_or_else(|| {
+                    RenderErrorReason::HashTypeMismatchForName(
+                        "business_days",
+                        "holidays".to_string(),
+                        "array of dates".to_string(),
+                    )
+                })?,
+        };
+
+        let skip = |date: &Date| date.weekday() >= 5 || holidays.contains(date);
+        self.0
+            .render("business_days", Step::Days(1), skip, h, r, ctx, rc, out)
+    }
+}
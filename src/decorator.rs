@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `*repeat` decorator
+
+use handlebars::*;
+
+use crate::{Error, RepeatHelper};
+
+/// The `repeat` decorator object
+///
+/// Rather than rendering, `{{*repeat 3 name="rows"}}` sets the `rows` key of
+/// the current context, such as the template data or the object of a `with`
+/// block, to an array of three elements, on which `each` and `lookup` may then
+/// operate. The elements are their indices unless the `fill` hash parameter
+/// gives a value for them all. The length is read as the count of the `repeat`
+/// helper is, with the options of the helper it is made from. To use, register
+/// it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_decorator("repeat", Box::new(handlebars_repeat::RepeatDecorator::default()));
+///
+/// let template = "{{*repeat 3 name=\"rows\" fill=\"-\"}}{{#each rows}}{{this}}{{@index}}{{/each}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "-0-1-2");
+/// ```
+///
+/// Its options, such as a maximum length, are those of a [`RepeatHelper`]:
+///
+/// ```rust
+/// use handlebars_repeat::{RepeatDecorator, RepeatHelper};
+///
+/// let decorator = RepeatDecorator::from(RepeatHelper::default().with_max_count(100));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RepeatDecorator(RepeatHelper);
+
+impl From<RepeatHelper> for RepeatDecorator {
+    fn from(helper: RepeatHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl RepeatDecorator {
+    /// Sets a maximum length for the arrays, as for the helper
+    pub fn with_max_count(self, max: u64) -> Self {
+        Self(self.0.with_max_count(max))
+    }
+}
+
+impl DecoratorDef for RepeatDecorator {
+    fn call<'reg: 'rc, 'rc>(
+        &'reg self,
+        d: &Decorator<'rc>,
+        _: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<(), RenderError> {
+        let count = d
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("repeat", 0))?;
+        let count = self
+            .0
+            .count(count.value(), || {
+                RenderErrorReason::ParamTypeMismatchForName(
+                    "repeat",
+                    "0".to_string(),
+                    "u64".to_string(),
+                )
+            })?
+            .unwrap_or(0);
+
+        if let Some(max) = self.0.max_count.filter(|max| count > *max) {
+            return Err(Error::TooManyRepetitions { count, max }.into());
+        }
+
+        let name = d
+            .hash_get("name")
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("repeat", "name".to_string()))?;
+        let name = name.value().as_str().ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                "name".to_string(),
+                "string".to_string(),
+            )
+        })?;
+
+        let fill = d.hash_get("fill").map(|fill| fill.value());
+        let items = (0..count)
+            .map(|i| fill.cloned().unwrap_or_else(|| i.into()))
+            .collect();
+
+        let invalid = || RenderErrorReason::InvalidJsonPath(name.to_string());
+        let items = JsonValue::Array(items);
+
+        // A block with a value of its own, such as a repetition, takes the
+        // array into that value.
+        if let Some(block) = rc.block_mut() {
+            if let Some(value) = block.base_value() {
+                let mut value = value.clone();
+                if !value.is_object() && !value.is_null() {
+                    return Err(invalid().into());
+                }
+
+                value[name] = items;
+                block.set_base_value(value);
+                return Ok(());
+            }
+        }
+
+        let mut data = match rc.context() {
+            Some(context) => context.data().clone(),
+            None => ctx.data().clone(),
+        };
+
+        // Otherwise, the array joins the data at the path of the block, such
+        // as that of `with` or `each`, where the paths of the block find it.
+        let pointer = rc.block().map_or_else(String::new, |block| {
+            block
+                .base_path()
+                .iter()
+                .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+                .collect()
+        });
+
+        // Only an object, or nothing at all, has room for the array.
+        match data.pointer_mut(&pointer) {
+            Some(value) if value.is_object() || value.is_null() => value[name] = items,
+            _ => return Err(invalid().into()),
+        }
+
+        rc.set_context(Context::wraps(data)?);
+        Ok(())
+    }
+}
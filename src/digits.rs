@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `digits` helper
+
+use handlebars::*;
+
+use crate::each;
+
+/// The greatest width to which the digits are padded
+const MAX_WIDTH: u64 = u16::MAX as u64;
+
+/// The `digits` handler object
+///
+/// `{{#digits 4096}}` renders its block once for each decimal digit of its
+/// parameter, from the most significant. The parameter is an integer, whose
+/// sign is ignored, or a string of decimal digits, which keeps any leading
+/// zeros and may be longer than an integer allows. The `width` hash parameter
+/// pads the digits with leading zeros to at least that many, up to 65535.
+///
+/// Each repetition is rendered with its digit, as a number, as the context,
+/// which is also the `@digit` local variable and the block parameter, if any.
+/// Otherwise, the local variables are those of `repeat`, so that `@rindex`
+/// gives the power of ten of the digit and `@first` and `@last` mark the most
+/// and least significant digits. To use, register it in your handlebars
+/// registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("digits", Box::new(handlebars_repeat::DigitsHelper::default()));
+///
+/// let template = "{{#digits 4096 width=6}}<span class=\"d{{@digit}}\"></span>{{/digits}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "<span class=\"d0\"></span><span class=\"d0\"></span><span class=\"d4\"></span>\
+///      <span class=\"d0\"></span><span class=\"d9\"></span><span class=\"d6\"></span>"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DigitsHelper {
+    max_count: Option<u64>,
+}
+
+impl DigitsHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+impl HelperDef for DigitsHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let number = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("digits", 0))?;
+
+        let digits = match number.value() {
+            JsonValue::Number(n) if n.is_u64() || n.is_i64() => {
+                n.to_string().trim_start_matches('-').to_string()
+            }
+
+            JsonValue::String(s) if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => {
+                s.clone()
+            }
+
+            _ => {
+                return Err(RenderErrorReason::ParamTypeMismatchForName(
+                    "digits",
+                    "0".to_string(),
+                    "integer or string of digits".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let width = match h.hash_get("width") {
+            None => 0,
+            Some(width) => width
+                .value()
+                .as_u64()
+                .filter(|width| *width <= MAX_WIDTH)
+                .ok_or_else(|| {
+                    RenderErrorReason::HashTypeMismatchForName(
+                        "digits",
+                        "width".to_string(),
+                        "at most 65535 digits".to_string(),
+                    )
+                })? as usize,
+        };
+
+        let padding = "0".repeat(width.saturating_sub(digits.len()));
+        let digits = padding + &digits;
+        let count = digits.len() as u64;
+
+        let items = digits.bytes().map(|digit| {
+            let digit = JsonValue::from(digit - b'0');
+
+            let mut item = each::Item::new(digit.clone());
+            item.locals.set("digit", digit);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
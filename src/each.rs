@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The loop shared by the helpers which repeat over a sequence of items
+//!
+//! Helpers such as `seq`, `hours` and `dates` differ only in the items they
+//! repeat over. Each item is rendered as `repeat` renders a repetition: with
+//! the local variables of its position, within a frame upon which `sep`,
+//! `break` and `acc` act.
+
+use handlebars::*;
+
+use crate::locals::{Locals, Position, Uses};
+use crate::{companions, frames, shared::Shared, Error};
+
+/// An item of a sequence
+pub(crate) struct Item {
+    /// The context of the repetition
+    pub context: JsonValue,
+
+    /// The block parameter, if other than the context
+    pub param: Option<JsonValue>,
+
+    /// The local variables of the item, besides those of its position
+    pub locals: Locals,
+}
+
+impl Item {
+    /// Creates an item with the given context and no local variables
+    pub(crate) fn new(context: impl Into<JsonValue>) -> Self {
+        Self {
+            context: context.into(),
+            param: None,
+            locals: Locals::default(),
+        }
+    }
+}
+
+/// Fails if the count exceeds the maximum, if any
+pub(crate) fn limit(count: u64, max_count: Option<u64>) -> Result<(), RenderError> {
+    match max_count.filter(|max| count > *max) {
+        Some(max) => Err(Error::TooManyRepetitions { count, max }.into()),
+        None => Ok(()),
+    }
+}
+
+/// Renders the block of the helper once for each of the given number of
+/// items, or the inverse block if there are none
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render<'reg: 'rc, 'rc>(
+    max_count: Option<u64>,
+    count: u64,
+    items: impl IntoIterator<Item = Item>,
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let mut items = items.into_iter();
+    unfold(max_count, count, |_| Ok(items.next()), h, r, ctx, rc, out)
+}
+
+/// Renders the block of the helper as `render` does, making each item after
+/// the first within the block context of the repetition before
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn unfold<'reg: 'rc, 'rc>(
+    max_count: Option<u64>,
+    count: u64,
+    mut next: impl FnMut(&mut RenderContext<'reg, 'rc>) -> Result<Option<Item>, RenderError>,
+    h: &Helper<'rc>,
+    r: &'reg Handlebars<'reg>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    limit(count, max_count)?;
+
+    let template = h
+        .template()
+        .ok_or(RenderErrorReason::BlockContentRequired)?;
+
+    let shared = Shared::of(h, r, ctx, rc);
+    let frame = frames::Frame::new(&shared, rc, None);
+    let mut following = match count {
+        0 => None,
+        _ => next(rc)?,
+    };
+
+    for n in 0..count {
+        let Item {
+            context,
+            param,
+            locals: own,
+        } = match following.take() {
+            Some(item) => item,
+            None => break,
+        };
+
+        let mut block = rc.block().cloned().unwrap_or_default();
+
+        if let Some(name) = h.block_param() {
+            let mut params = BlockParams::new();
+            params.add_value(name, param.unwrap_or_else(|| context.clone()))?;
+            block.set_block_params(params);
+        }
+
+        block.set_base_value(context);
+
+        let position = Position {
+            n,
+            i: n,
+            index: n,
+            count,
+            skip: 0,
+            end: count,
+        };
+        let mut locals = Locals::default();
+        position.set(&mut locals, &Uses::All);
+        locals.extend(own);
+
+        frame.set(&mut block, Some(n == count - 1), Some(locals.to_json()));
+        for (name, value) in locals {
+            block.set_local_var(&name, value);
+        }
+
+        let mut signal = None;
+        rc.push_block(block);
+        let mut result = companions::catch(template.render(r, ctx, rc, out), &mut signal);
+        if result.is_ok() && n + 1 < count && signal != Some(companions::Signal::Break) {
+            result = next(rc).map(|item| following = item);
+        }
+        rc.pop_block();
+        result?;
+
+        if signal == Some(companions::Signal::Break) {
+            break;
+        }
+    }
+
+    frame.end();
+
+    if count == 0 {
+        if let Some(template) = h.inverse() {
+            template.render(r, ctx, rc, out)?;
+        }
+    }
+
+    Ok(())
+}
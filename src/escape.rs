@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Escape functions selectable with the `escape` hash parameter
+//!
+//! The escape function belongs to the registry, which a helper cannot change.
+//! Instead, a block is copied with each escaped expression wrapped in a local
+//! helper, which renders the expression unescaped and then escapes its output.
+
+use std::collections::{BTreeSet, HashMap};
+
+use handlebars::template::{DecoratorTemplate, HelperTemplate, Parameter, TemplateElement};
+use handlebars::*;
+
+/// An escape function, as registered with a registry
+type EscapeFn = fn(&str) -> String;
+
+/// The local helpers which escape the output of their block
+const ESCAPERS: [(&str, EscapeFn); 2] = [
+    ("repeat-escape-html", html_escape),
+    ("repeat-escape-json", json),
+];
+
+/// Escapes a string for inclusion within a JSON string literal
+pub(crate) fn json(data: &str) -> String {
+    let mut escaped = String::with_capacity(data.len());
+
+    for c in data.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Escapes the output of its block, unless escaping is disabled
+struct Escaper(EscapeFn);
+
+impl HelperDef for Escaper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let template = h
+            .template()
+            .ok_or(RenderErrorReason::BlockContentRequired)?;
+
+        // An enclosing `escape="none"` block disables escaping.
+        if rc.is_disable_escape() {
+            return template.render(r, ctx, rc, out);
+        }
+
+        let mut buffer = StringOutput::new();
+        rc.set_disable_escape(true);
+        let result = template.render(r, ctx, rc, &mut buffer);
+        rc.set_disable_escape(false);
+        result?;
+
+        out.write(&(self.0)(&buffer.into_string()?))?;
+        Ok(())
+    }
+}
+
+/// A block, and the partials it names, with every escaped expression wrapped
+pub(crate) struct Escaped {
+    escaper: usize,
+    template: Option<Template>,
+    inverse: Option<Template>,
+    partials: Vec<(String, Template)>,
+}
+
+impl Escaped {
+    /// Copies the block and inverse, wrapping their expressions in the escaper
+    /// for JSON or for HTML
+    pub(crate) fn new(
+        template: Option<&Template>,
+        inverse: Option<&Template>,
+        json: bool,
+        r: &Handlebars<'_>,
+        rc: &RenderContext<'_, '_>,
+    ) -> Self {
+        let escaper = usize::from(json);
+        let mut names = BTreeSet::new();
+        let template = template.map(|t| rewrite(t, escaper, &mut names));
+        let inverse = inverse.map(|t| rewrite(t, escaper, &mut names));
+
+        // The partials named by the block are copied too, as are those they
+        // name in turn. Partials chosen by a subexpression cannot be known.
+        let mut partials = Vec::new();
+        let mut seen = BTreeSet::new();
+        while let Some(name) = names.pop_first() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+
+            if let Some(partial) = rc.get_partial(&name).or_else(|| r.get_template(&name)) {
+                partials.push((name, rewrite(partial, escaper, &mut names)));
+            }
+        }
+
+        Self {
+            escaper,
+            template,
+            inverse,
+            partials,
+        }
+    }
+
+    /// The copy of the block
+    pub(crate) fn template(&self) -> Option<&Template> {
+        self.template.as_ref()
+    }
+
+    /// The copy of the inverse block
+    pub(crate) fn inverse(&self) -> Option<&Template> {
+        self.inverse.as_ref()
+    }
+
+    /// Prepares a render context for the copies, with escaping enabled
+    pub(crate) fn install<'rc>(&'rc self, rc: &mut RenderContext<'_, 'rc>) {
+        let (name, escape) = ESCAPERS[self.escaper];
+        rc.register_local_helper(name, Box::new(Escaper(escape)));
+        for (name, partial) in &self.partials {
+            rc.set_partial(name.clone(), partial);
+        }
+
+        rc.set_disable_escape(false);
+    }
+}
+
+/// Copies a template, wrapping each escaped expression in the escaper and
+/// collecting the names of the partials
+fn rewrite(template: &Template, escaper: usize, partials: &mut BTreeSet<String>) -> Template {
+    let helper = |ht: &HelperTemplate, partials: &mut BTreeSet<String>| HelperTemplate {
+        name: ht.name.clone(),
+        params: ht.params.clone(),
+        hash: ht.hash.clone(),
+        block_param: ht.block_param.clone(),
+        template: ht.template.as_ref().map(|t| rewrite(t, escaper, partials)),
+        inverse: ht.inverse.as_ref().map(|t| rewrite(t, escaper, partials)),
+        block: ht.block,
+        chain: ht.chain,
+    };
+
+    let decorator = |dt: &DecoratorTemplate, partials: &mut BTreeSet<String>| DecoratorTemplate {
+        name: dt.name.clone(),
+        params: dt.params.clone(),
+        hash: dt.hash.clone(),
+        template: dt.template.as_ref().map(|t| rewrite(t, escaper, partials)),
+        indent: dt.indent.clone(),
+    };
+
+    let elements = template.elements.iter().enumerate().map(|(i, element)| {
+        let wrap = |expression: TemplateElement| {
+            let (name, _) = ESCAPERS[escaper];
+            TemplateElement::HelperBlock(Box::new(HelperTemplate {
+                name: Parameter::Name(name.into()),
+                params: Vec::new(),
+                hash: HashMap::new(),
+                block_param: None,
+                template: Some(Template {
+                    name: template.name.clone(),
+                    elements: vec![expression],
+                    mapping: template.mapping.get(i).cloned().into_iter().collect(),
+                }),
+                inverse: None,
+                block: true,
+                chain: false,
+            }))
+        };
+
+        match element {
+            TemplateElement::Expression(..) => wrap(element.clone()),
+
+            // The expression of an enclosing block's escaper is rewrapped, so
+            // that the innermost `escape` applies.
+            TemplateElement::HelperBlock(ht) if is_escaper(ht) => {
+                match ht.template.as_ref().and_then(|t| t.elements.first()) {
+                    Some(expression) => wrap(expression.clone()),
+                    None => element.clone(),
+                }
+            }
+
+            TemplateElement::HelperBlock(ht) => {
+                TemplateElement::HelperBlock(Box::new(helper(ht, partials)))
+            }
+
+            TemplateElement::DecoratorBlock(dt) => {
+                TemplateElement::DecoratorBlock(Box::new(decorator(dt, partials)))
+            }
+
+            TemplateElement::PartialExpression(dt) | TemplateElement::PartialBlock(dt) => {
+                if let Some(name) = dt.name.as_name().filter(|n| !n.starts_with('@')) {
+                    partials.insert(name.to_string());
+                }
+
+                match element {
+                    TemplateElement::PartialBlock(..) => {
+                        TemplateElement::PartialBlock(Box::new(decorator(dt, partials)))
+                    }
+                    _ => element.clone(),
+                }
+            }
+
+            _ => element.clone(),
+        }
+    });
+
+    Template {
+        name: template.name.clone(),
+        elements: elements.collect(),
+        mapping: template.mapping.clone(),
+    }
+}
+
+/// Whether a helper block is one of the escapers
+fn is_escaper(ht: &HelperTemplate) -> bool {
+    matches!(&ht.name, Parameter::Name(name) if ESCAPERS.iter().any(|(n, _)| n == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("plain <b>&</b>", "plain <b>&</b>")]
+    #[case("say \"hi\"", "say \\\"hi\\\"")]
+    #[case("a\\b", "a\\\\b")]
+    #[case("a\nb\r\tc", "a\\nb\\r\\tc")]
+    #[case("\u{0}\u{1f}", "\\u0000\\u001f")]
+    #[case("é", "é")]
+    fn escape_json(#[case] data: &str, #[case] escaped: &str) {
+        assert_eq!(json(data), escaped);
+    }
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `every` helper
+
+use handlebars::*;
+
+use crate::each;
+
+/// Parses a duration such as `15m`, `1h30m` or `90s` into seconds
+///
+/// A duration is a sequence of whole numbers, each followed by a unit of days
+/// (`d`), hours (`h`), minutes (`m`) or seconds (`s`).
+fn seconds(s: &str) -> Option<u64> {
+    let mut total = 0u64;
+    let mut digits = 0;
+
+    for (i, c) in s.char_indices() {
+        let unit = match c {
+            '0'..='9' => continue,
+            'd' => 86_400,
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        let n: u64 = s[digits..i].parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        digits = i + 1;
+    }
+
+    match digits == s.len() && !s.is_empty() {
+        true => Some(total),
+        false => None,
+    }
+}
+
+/// The `every` handler object
+///
+/// `{{#every "15m" for="2h"}}` renders its block once for each multiple of the
+/// duration of its parameter up to and including the duration of the `for`
+/// hash parameter: here, after 15 minutes, 30 minutes and so on up to two
+/// hours. A duration is a sequence of whole numbers followed by units of days
+/// (`d`), hours (`h`), minutes (`m`) or seconds (`s`), such as `1h30m`. Setting
+/// the `from_zero` hash parameter to `true` begins with the offset of zero.
+///
+/// Each repetition is rendered with its offset as the context, which is also
+/// the `@offset` local variable and the block parameter, if any. It is in the
+/// `HH:MM` form, or `HH:MM:SS` when the step has seconds, and the hours are
+/// not limited to a day. The `@seconds` local variable holds the offset in
+/// seconds, along with `@index`, `@first` and `@last`. To use, register it in
+/// your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("every", Box::new(handlebars_repeat::EveryHelper::default()));
+///
+/// let template = "{{#every \"20m\" for=\"1h\"}}{{@offset}}={{@seconds}} {{/every}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "00:20=1200 00:40=2400 01:00=3600 "
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EveryHelper {
+    max_count: Option<u64>,
+}
+
+impl EveryHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+impl HelperDef for EveryHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let step = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("every", 0))?;
+        let step = step
+            .value()
+            .as_str()
+            .and_then(seconds)
+            .filter(|step| *step > 0)
+            .ok_or_else(|| {
+                RenderErrorReason::ParamTypeMismatchForName(
+                    "every",
+                    "0".to_string(),
+                    "non-zero duration".to_string(),
+                )
+            })?;
+
+        let total = h
+            .hash_get("for")
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("every", "for".to_string()))?;
+        let total = total.value().as_str().and_then(seconds).ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "every",
+                "for".to_string(),
+                "duration".to_string(),
+            )
+        })?;
+
+        let from_zero = h
+            .hash_get("from_zero")
+            .map_or(false, |from_zero| from_zero.value().is_truthy(false));
+
+        let first = u64::from(!from_zero);
+        let count = total / step + 1 - first;
+        let items = (0..count).map(|n| {
+            let seconds = (first + n) * step;
+            let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+            let offset = match step % 60 {
+                0 => format!("{:02}:{:02}", hours, minutes),
+                _ => format!("{:02}:{:02}:{:02}", hours, minutes, seconds % 60),
+            };
+
+            let mut item = each::Item::new(offset.clone());
+            item.locals.set("offset", offset);
+            item.locals.set("seconds", seconds);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("15m", Some(900))]
+    #[case("1h30m", Some(5400))]
+    #[case("90s", Some(90))]
+    #[case("1d2h3m4s", Some(93_784))]
+    #[case("0s", Some(0))]
+    #[case("", None)]
+    #[case("15", None)]
+    #[case("m", None)]
+    #[case("1.5h", None)]
+    #[case("1w", None)]
+    #[case("99999999999999999999s", None)]
+    fn durations(#[case] duration: &str, #[case] secs: Option<u64>) {
+        assert_eq!(seconds(duration), secs);
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expressions evaluated again for each repetition
+
+use std::borrow::Cow;
+
+use handlebars::template::Parameter;
+use handlebars::*;
+
+/// An expression which a helper evaluates again for each repetition
+///
+/// Handlebars evaluates the parameters of a helper once, before calling it.
+/// An expression which depends upon the repetition is therefore given either
+/// as a path, which is looked up again, or as a string holding a path or a
+/// subexpression, such as `"(eq @index 3)"`, which is parsed once. The
+/// parentheses of a subexpression may be left out, as in `"gt @index 3"`.
+#[derive(Clone, Debug)]
+pub(crate) enum Expression {
+    /// A path, looked up within the block context
+    Path(String),
+
+    /// Any other parameter, such as a subexpression
+    Parameter(Parameter),
+}
+
+impl Expression {
+    /// Reads the expression of a parameter, unless it is neither a path nor a
+    /// string which parses as one or as a subexpression
+    pub(crate) fn new(param: &PathAndJson<'_>) -> Option<Self> {
+        if let Some(path) = param.relative_path() {
+            return Some(Self::Path(path.clone()));
+        }
+
+        let source = param.value().as_str()?.trim();
+        let source = match source.starts_with('(') || !source.contains(char::is_whitespace) {
+            true => Cow::Borrowed(source),
+            false => Cow::Owned(format!("({})", source)),
+        };
+
+        match Parameter::parse(&source).ok()? {
+            parameter @ Parameter::Path(..) => parameter.as_name().map(|p| Self::Path(p.into())),
+            Parameter::Name(..) => None,
+            parameter => Some(Self::Parameter(parameter)),
+        }
+    }
+
+    /// Evaluates the expression within the current block context
+    pub(crate) fn evaluate<'reg: 'rc, 'rc, T>(
+        &self,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &RenderContext<'reg, 'rc>,
+        f: impl FnOnce(&JsonValue) -> T,
+    ) -> Result<T, RenderError> {
+        match self {
+            Self::Path(path) => Ok(f(rc.evaluate(ctx, path)?.as_json())),
+
+            // The parsed parameter lives only as long as the helper call, so
+            // it is expanded with a render context of that shorter lifetime.
+            Self::Parameter(parameter) => {
+                let mut rc = rc.clone();
+                Ok(f(parameter.expand(r, ctx, &mut rc)?.value()))
+            }
+        }
+    }
+
+    /// Evaluates whether the expression holds within the current block context
+    pub(crate) fn holds<'reg: 'rc, 'rc>(
+        &self,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &RenderContext<'reg, 'rc>,
+    ) -> Result<bool, RenderError> {
+        self.evaluate(r, ctx, rc, |value| value.is_truthy(false))
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Printf-style formatting of the index
+
+/// The greatest width of a conversion, that of a `u64` in binary
+const MAX_WIDTH: usize = 64;
+
+/// The conversion of a printf-style format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Conversion {
+    Decimal,
+    LowerHex,
+    UpperHex,
+    Octal,
+    Binary,
+}
+
+/// A parsed printf-style format, such as `reg_%04x`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Format {
+    prefix: String,
+    zero: bool,
+    width: usize,
+    conversion: Conversion,
+    suffix: String,
+}
+
+impl Format {
+    /// Parses a format containing exactly one conversion
+    ///
+    /// The conversion is a `%` followed by an optional `0` flag, an optional
+    /// width of at most 64 and one of `d`, `x`, `X`, `o` or `b`. A literal
+    /// `%` is written as `%%`.
+    pub(crate) fn parse(format: &str) -> Option<Self> {
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let mut spec = None;
+
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            let text = match spec {
+                None => &mut prefix,
+                Some(..) => &mut suffix,
+            };
+
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                text.push('%');
+                continue;
+            }
+
+            if spec.is_some() {
+                return None;
+            }
+
+            let zero = chars.peek() == Some(&'0');
+            if zero {
+                chars.next();
+            }
+
+            let mut width = 0usize;
+            while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                chars.next();
+                width = width.checked_mul(10)?.checked_add(digit as usize)?;
+                if width > MAX_WIDTH {
+                    return None;
+                }
+            }
+
+            let conversion = match chars.next()? {
+                'd' => Conversion::Decimal,
+                'x' => Conversion::LowerHex,
+                'X' => Conversion::UpperHex,
+                'o' => Conversion::Octal,
+                'b' => Conversion::Binary,
+                _ => return None,
+            };
+
+            spec = Some((zero, width, conversion));
+        }
+
+        let (zero, width, conversion) = spec?;
+        Some(Self {
+            prefix,
+            zero,
+            width,
+            conversion,
+            suffix,
+        })
+    }
+
+    /// Formats a number
+    pub(crate) fn format(&self, n: u64) -> String {
+        let digits = match self.conversion {
+            Conversion::Decimal => n.to_string(),
+            Conversion::LowerHex => format!("{:x}", n),
+            Conversion::UpperHex => format!("{:X}", n),
+            Conversion::Octal => format!("{:o}", n),
+            Conversion::Binary => format!("{:b}", n),
+        };
+
+        self.pad("", &digits)
+    }
+
+    /// Formats a signed number
+    ///
+    /// As with printf, negative numbers are signed only in decimal, and are
+    /// otherwise formatted as their two's complement.
+    pub(crate) fn format_signed(&self, n: i64) -> String {
+        match self.conversion {
+            Conversion::Decimal if n < 0 => self.pad("-", &n.unsigned_abs().to_string()),
+            _ => self.format(n as u64),
+        }
+    }
+
+    /// Pads the signed digits to the width, within the prefix and suffix
+    fn pad(&self, sign: &str, digits: &str) -> String {
+        let padding = self.width.saturating_sub(sign.len() + digits.len());
+        let (zeros, spaces) = if self.zero {
+            (padding, 0)
+        } else {
+            (0, padding)
+        };
+
+        format!(
+            "{}{}{}{}{}{}",
+            self.prefix,
+            " ".repeat(spaces),
+            sign,
+            "0".repeat(zeros),
+            digits,
+            self.suffix
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("%d", 42, "42")]
+    #[case("%04x", 255, "00ff")]
+    #[case("%X", 255, "FF")]
+    #[case("0x%08X", 48879, "0x0000BEEF")]
+    #[case("%o", 8, "10")]
+    #[case("%08b", 5, "00000101")]
+    #[case("%4d", 7, "   7")]
+    #[case("%2d", 1234, "1234")]
+    #[case("reg_%02d_%%", 3, "reg_03_%")]
+    #[case(
+        "%064b",
+        1,
+        "0000000000000000000000000000000000000000000000000000000000000001"
+    )]
+    fn valid(#[case] spec: &str, #[case] n: u64, #[case] output: &str) {
+        assert_eq!(Format::parse(spec).unwrap().format(n), output);
+    }
+
+    #[rstest]
+    #[case("%d", -42, "-42")]
+    #[case("%05d", -42, "-0042")]
+    #[case("%5d", -42, "  -42")]
+    #[case("%02d", 7, "07")]
+    #[case("%x", -1, "ffffffffffffffff")]
+    #[case("%d", i64::MIN, "-9223372036854775808")]
+    fn signed(#[case] spec: &str, #[case] n: i64, #[case] output: &str) {
+        assert_eq!(Format::parse(spec).unwrap().format_signed(n), output);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("x")]
+    #[case("%%")]
+    #[case("%")]
+    #[case("%04")]
+    #[case("%s")]
+    #[case("%d%d")]
+    #[case("%-4d")]
+    #[case("%99999999999999999999999d")]
+    #[case("%065d")]
+    #[case("%1000000000d")]
+    fn invalid(#[case] spec: &str) {
+        assert_eq!(Format::parse(spec), None);
+    }
+}
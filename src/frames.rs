@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The looping blocks being rendered
+//!
+//! Intermediate block helpers, such as `each`, push block contexts of their
+//! own which hide those of an enclosing `repeat`. Each block pushed for a
+//! repetition therefore carries its frame as a local variable, which the
+//! blocks copied from it inherit, and the frame of the enclosing repetition
+//! is otherwise found further down the stack of blocks.
+
+use handlebars::{BlockContext, JsonValue, RenderContext};
+
+use crate::shared::Shared;
+
+/// The name of the local variable holding the frame of a block
+const FRAME: &str = "repeat-frame";
+
+/// Applies a function to the frame of the nearest enclosing repetition, if any
+fn nearest<T>(rc: &RenderContext<'_, '_>, f: impl FnOnce(&JsonValue) -> T) -> Option<T> {
+    let frame =
+        |rc: &RenderContext<'_, '_>| rc.block().and_then(|b| b.get_local_var(FRAME)).cloned();
+
+    // The current block usually holds the frame, sparing a copy of the stack.
+    if let Some(frame) = frame(rc) {
+        return Some(f(&frame));
+    }
+
+    let mut rc = rc.clone();
+    while rc.block().is_some() {
+        rc.pop_block();
+        if let Some(frame) = frame(&rc) {
+            return Some(f(&frame));
+        }
+    }
+
+    None
+}
+
+/// A looping block being rendered
+pub(crate) struct Frame {
+    shared: Shared,
+    id: u64,
+    depth: u64,
+    label: Option<String>,
+    outer: Option<JsonValue>,
+    labelled: JsonValue,
+}
+
+impl Frame {
+    /// Begins a frame, optionally labelled, within those of the render context
+    pub(crate) fn new(shared: &Shared, rc: &RenderContext<'_, '_>, label: Option<&str>) -> Self {
+        let parent = nearest(rc, JsonValue::clone);
+
+        let mut labelled = JsonValue::Array(Vec::new());
+        if let Some(parent) = &parent {
+            labelled = parent["labelled"].clone();
+            if let (Some(label), JsonValue::Array(all)) = (parent["label"].as_str(), &mut labelled)
+            {
+                all.push(vec![JsonValue::from(label), parent["locals"].clone()].into());
+            }
+        }
+
+        Self {
+            shared: shared.clone(),
+            id: shared.frame(),
+            depth: parent
+                .as_ref()
+                .map_or(0, |p| p["depth"].as_u64().unwrap_or_default() + 1),
+            label: label.map(String::from),
+            outer: parent.map(|p| p["locals"].clone()),
+            labelled,
+        }
+    }
+
+    /// The number of enclosing looping blocks
+    pub(crate) fn depth(&self) -> u64 {
+        self.depth
+    }
+
+    /// Records the frame in the block of a repetition, with whether it is the
+    /// last, if known, and its local variables, if any nested block may read
+    /// them
+    pub(crate) fn set(
+        &self,
+        block: &mut BlockContext<'_>,
+        last: Option<bool>,
+        locals: Option<JsonValue>,
+    ) {
+        let mut frame = JsonValue::Null;
+        frame["id"] = self.id.into();
+        frame["depth"] = self.depth.into();
+        frame["label"] = self.label.clone().into();
+        frame["locals"] = locals.unwrap_or_default();
+        frame["last"] = last.into();
+        frame["labelled"] = self.labelled.clone();
+        block.set_local_var(FRAME, frame);
+    }
+
+    /// The local variables of the current repetition of the enclosing block
+    pub(crate) fn outer(&self) -> Option<JsonValue> {
+        self.outer.clone()
+    }
+
+    /// The local variables of the enclosing blocks which have labels
+    pub(crate) fn labelled(&self) -> Vec<(String, JsonValue)> {
+        let all = self.labelled.as_array().into_iter().flatten();
+        all.filter_map(|l| Some((l[0].as_str()?.to_string(), l[1].clone())))
+            .collect()
+    }
+
+    /// The accumulators of the block, as an object
+    pub(crate) fn accumulated(&self) -> JsonValue {
+        self.shared.accumulate(self.id, |a| a.clone())
+    }
+
+    /// Ends the frame, publishing its accumulators to the rest of the render
+    pub(crate) fn end(self) {
+        self.shared.publish(self.id);
+    }
+}
+
+/// Whether a looping block is being rendered
+pub(crate) fn within(rc: &RenderContext<'_, '_>) -> bool {
+    nearest(rc, |_| ()).is_some()
+}
+
+/// Whether the current repetition of the innermost block is its last, if the
+/// block records it
+pub(crate) fn last(rc: &RenderContext<'_, '_>) -> Option<bool> {
+    nearest(rc, |frame| frame["last"].as_bool()).flatten()
+}
+
+/// Applies a function to the accumulators of the innermost block, if any
+pub(crate) fn accumulators<T>(
+    shared: &Shared,
+    rc: &RenderContext<'_, '_>,
+    f: impl FnOnce(&mut JsonValue) -> T,
+) -> Option<T> {
+    let id = nearest(rc, |frame| frame["id"].as_u64()).flatten()?;
+    Some(shared.accumulate(id, f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nesting() {
+        let shared = Shared::default();
+        let mut rc = RenderContext::new(None);
+        assert!(!within(&rc));
+
+        let outer = Frame::new(&shared, &rc, Some("row"));
+        assert_eq!(outer.depth(), 0);
+        assert_eq!(outer.outer(), None);
+        assert_eq!(outer.labelled(), vec![]);
+
+        let mut block = BlockContext::new();
+        outer.set(&mut block, Some(true), Some(1.into()));
+        rc.push_block(block);
+        assert!(within(&rc));
+        assert_eq!(last(&rc), Some(true));
+        accumulators(&shared, &rc, |a| a["x"] = 1.into());
+        assert_eq!(outer.accumulated()["x"], 1);
+
+        // A block pushed by another helper hides the frame.
+        rc.push_block(BlockContext::new());
+        assert_eq!(last(&rc), Some(true));
+
+        let middle = Frame::new(&shared, &rc, None);
+        let mut block = BlockContext::new();
+        middle.set(&mut block, None, Some(2.into()));
+        rc.push_block(block);
+        assert_eq!(last(&rc), None);
+
+        let inner = Frame::new(&shared, &rc, None);
+        assert_eq!(inner.depth(), 2);
+        assert_eq!(inner.outer(), Some(2.into()));
+        assert_eq!(inner.labelled(), vec![("row".into(), 1.into())]);
+        assert_eq!(inner.accumulated(), JsonValue::Null);
+
+        rc.pop_block();
+        rc.pop_block();
+        assert_eq!(Frame::new(&shared, &rc, None).depth(), 1);
+        rc.pop_block();
+        assert!(!within(&rc));
+
+        outer.end();
+        assert_eq!(shared.published("x"), Some(1.into()));
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `hours` helper
+
+use handlebars::*;
+
+use crate::each;
+
+/// The `hours` handler object
+///
+/// `{{#hours from=9 to=17}}` renders its block once for each hour of the day
+/// from the `from` hour up to the `to` hour, which is excluded unless the
+/// `inclusive` hash parameter is `true`. The `step` hash parameter gives a
+/// different step in hours, such as `0.5` for half hours; the bounds may also
+/// be fractional, and all of them are rounded to whole minutes. The hours lie
+/// between 0 and 24, though the day ends before 24:00, which is never rendered
+/// even when `inclusive`.
+///
+/// Each repetition is rendered with its time of day, in the `HH:MM` form, as
+/// the context, which is also the `@hour` local variable and the block
+/// parameter, if any. The `@value` local variable holds the time in hours, and
+/// otherwise the local variables are those of `repeat`, such as `@index`,
+/// `@first`, `@last` and `@even`. The `sep` and `break` helpers act upon the
+/// `hours` block. When the range is empty, the inverse block is rendered. To
+/// use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("hours", Box::new(handlebars_repeat::HoursHelper::default()));
+///
+/// let template = "{{#hours from=9 to=11 step=0.5}}{{@hour}} {{/hours}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "09:00 09:30 10:00 10:30 ");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HoursHelper {
+    max_count: Option<u64>,
+}
+
+impl HoursHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// The minutes in a day
+const DAY: u64 = 24 * 60;
+
+/// Parses the named hash parameter as a number of hours, in minutes
+fn minutes(h: &Helper<'_>, name: &'static str) -> Result<Option<u64>, RenderError> {
+    let value = match h.hash_get(name) {
+        None => return Ok(None),
+        Some(value) => value.value(),
+    };
+
+    match value.as_f64() {
+        Some(hours) if (0.0..=24.0).contains(&hours) => Ok(Some((hours * 60.0).round() as u64)),
+        _ => Err(RenderErrorReason::HashTypeMismatchForName(
+            "hours",
+            name.to_string(),
+            "hours between 0 and 24".to_string(),
+        )
+        .into()),
+    }
+}
+
+impl HelperDef for HoursHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let from = minutes(h, "from")?
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("hours", "from".to_string()))?;
+        let to = minutes(h, "to")?
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("hours", "to".to_string()))?;
+
+        let step = match minutes(h, "step")? {
+            None => 60,
+            Some(step) if step > 0 => step,
+            Some(..) => {
+                return Err(RenderErrorReason::HashTypeMismatchForName(
+                    "hours",
+                    "step".to_string(),
+                    "at least a minute".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let inclusive = match h.hash_get("inclusive").map(|v| v.value()) {
+            None => false,
+            Some(inclusive) => inclusive.as_bool().ok_or_else(|| {
+                RenderErrorReason::HashTypeMismatchForName(
+                    "hours",
+                    "inclusive".to_string(),
+                    "bool".to_string(),
+                )
+            })?,
+        };
+
+        let count = match to.checked_sub(from) {
+            None => 0,
+            Some(span) if inclusive => span / step + 1,
+            Some(span) => (span + step - 1) / step,
+        };
+
+        // The day ends before midnight, so 24:00 is never rendered.
+        let count = count.min((DAY - from + step - 1) / step);
+
+        let items = (0..count).map(|n| {
+            let minutes = from + n * step;
+            let hour = format!("{:02}:{:02}", minutes / 60, minutes % 60);
+
+            let mut item = each::Item::new(hour.clone());
+            item.locals.set("hour", hour);
+            item.locals.set("value", minutes as f64 / 60.0);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
@@ -17,14 +17,171 @@
 //! hi
 //! ```
 //!
+//! The `count` may also be given as the `count` (or `times`) hash parameter,
+//! which reads better in large templates: `{{#repeat count=3}}` is equivalent
+//! to the example above. Templates in the idiom of other engines may register
+//! the same helper as `times` (see [`TimesHelper`]) and write `{{#times 3}}`.
+//!
+//! With `len=true`, a string is repeated once per character. This is handy for
+//! underlining headings in plain text output. For example, given
+//! `{"title": "Repeat"}`:
+//!
+//! ```notrust
+//! {{title}}
+//! {{#repeat title len=true}}={{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Repeat
+//! ======
+//! ```
+//!
+//! To count bytes rather than characters, use `len="bytes"`.
+//!
+//! If the `count` lives elsewhere in the data, the `count_path` hash parameter
+//! resolves it as a dotted path from the root of the data, regardless of the
+//! current context: `{{#repeat count_path="config.replicas"}}`.
+//!
+//! Numeric parameters may also be given as strings containing a decimal
+//! integer, such as `"3"`, which is common for data originating from
+//! environment variables or YAML front-matter. Fractional numbers, such as
+//! `2.5`, are rejected unless a `Rounding` policy is configured. Booleans
+//! are likewise rejected unless `RepeatHelper::with_booleans` is enabled, in
+//! which case `true` repeats once and `false` renders the inverse block.
+//!
+//! A `null` or missing `count` is an error. When rendering incomplete data,
+//! `RepeatHelper::with_lenient` treats such counts as zero instead, so that
+//! the inverse block is rendered. Alternatively, `RepeatHelper::with_default`
+//! supplies the `count` used when none is given at all, so that a bare
+//! `{{#repeat}}` may serve as a placeholder.
+//!
+//! A negative `count` is likewise an error, unless a `Negative` policy is
+//! configured to clamp it to zero or to render the inverse block.
+//!
+//! ## Arrays
+//!
+//! When given an array, `repeat` repeats the block once per element. Like the
+//! standard `each` helper function, the current element becomes the context
+//! of the block. For example, given `{"names": ["foo", "bar"]}`:
+//!
+//! ```notrust
+//! {{#repeat names}}
+//! {{@index}}: {{this}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0: foo
+//! 1: bar
+//! ```
+//!
+//! All of the options below apply to arrays as well.
+//!
+//! ## Objects
+//!
+//! When given an object, `repeat` reads the `count` from its `count` key (the
+//! key may be changed with `RepeatHelper::with_count_key`). The remaining
+//! fields become the context of the block. For example, given
+//! `{"replicas": {"count": 2, "name": "web"}}`:
+//!
+//! ```notrust
+//! {{#repeat replicas}}
+//! {{name}}-{{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! web-0
+//! web-1
+//! ```
+//!
+//! Alternatively, `keys=true` repeats the block once per key of the object,
+//! without changing the context: given the data above,
+//! `{{#repeat replicas keys=true}}?{{/repeat}}` produces `??`.
+//!
+//! ## Rebinding the Context
+//!
+//! The `this` hash parameter rebinds the context of each repetition to a path,
+//! in which `{i}` is replaced with the value of `@index`. This permits indexed
+//! access patterns that `each` cannot express. For example, given
+//! `{"a": ["x", "y", "z"], "b": ["1", "2", "3"]}`:
+//!
+//! ```notrust
+//! {{#repeat 3 this="a.[{i}]" as |i|}}
+//! {{this}}{{lookup @root.b i}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! x1
+//! y2
+//! z3
+//! ```
+//!
 //! ## Local Variables
 //!
-//! Within the repeated block, there are three local variables in addition to
-//! the standard context:
+//! Within the repeated block, there are several local variables in addition
+//! to the standard context:
 //!
 //! 1. `@index` is an integer indicating the index of the current repetition.
 //! 2. `@first` is a boolean indicating whether this is the first repetation.
 //! 3. `@last` is a boolean indicating whether this is the last repetation.
+//! 4. `@rindex` is an integer counting down the repetitions to zero.
+//! 5. `@even` and `@odd` are booleans alternating with each repetition,
+//!    starting with `@even`, to ease zebra striping.
+//! 6. `@count` is an integer indicating the total number of repetitions.
+//! 7. `@middle` is a boolean indicating whether this is neither the first nor
+//!    the last repetition.
+//! 8. `@index1` is `@index` plus one, for display. It is unset when `@index`
+//!    is the greatest `u64`.
+//! 9. `@remaining` is an integer indicating the number of repetitions still to
+//!    be rendered after this one, in whichever order. Like `@first` and
+//!    `@last`, it counts only those of the window (see below).
+//! 10. `@progress` is a float from just above `0.0` to `1.0`, and `@percent`
+//!     the corresponding integer up to `100`, indicating the share of the
+//!     repetitions done once this one is rendered.
+//! 11. `@parity` is the string `even` or `odd`, matching `@even` and `@odd`,
+//!     for use in class names.
+//! 12. `@index0` is `@index` as a string, zero-padded to the number of digits
+//!     in `@count`, for use in file names and identifiers.
+//! 13. `@alpha` and `@ALPHA` are `@index` as lowercase and uppercase letters,
+//!     counting `a`, `b`, ..., `z`, `aa`, `ab` and so on.
+//! 14. `@roman` and `@ROMAN` are `@index1` as lowercase and uppercase roman
+//!     numerals, up to 3999.
+//! 15. `@ordinal` is `@index1` as an English ordinal, such as `1st`, `2nd` or
+//!     `3rd`.
+//! 16. `@ident` is a C identifier combining the `ident_prefix` hash parameter,
+//!     an underscore and `@index`, such as `item_0`. Characters which are not
+//!     valid in identifiers are replaced with underscores.
+//! 17. `@depth` is an integer indicating the number of enclosing `repeat`
+//!     blocks, counting from zero for the outermost, even through intermediate
+//!     block helpers such as `each`.
+//! 18. `@outer` is an object holding the local variables of the enclosing
+//!     `repeat` block, even through intermediate block helpers such as
+//!     `each`. Since handlebars does not descend into local variables, its
+//!     members are read with `lookup`, as in `{{lookup @outer "index"}}`, or
+//!     as flattened local variables, as in `{{@outer_index}}`.
+//! 19. `@global_index` is an integer counting the innermost repetitions of the
+//!     render, those with no repetitions nested within them, continuing from
+//!     one `repeat` block to the next, for continuous numbering. A repetition
+//!     with nested repetitions shares the global index of the first of them,
+//!     and one skipped by `on_error` still uses up its own.
+//! 20. `@index_str` is the index as a string, which stays exact even where
+//!     JSON numbers are read as doubles, losing precision above 2^53.
+//! 21. `@phase` is `"start"` for the first repetition, `"end"` for the last,
+//!     `"only"` when they are one and the same and `"middle"` otherwise.
+//!
+//! Every local variable is built for each repetition, so that a custom helper
+//! may read any of them from the block context. For large counts,
+//! `RepeatHelper::with_lazy_locals` builds only those which the block names.
 //!
 //! For example:
 //!
@@ -42,160 +199,6153 @@
 //! Index: 2 (first: false; last: true)
 //! ```
 //!
-//! ## Inverse Block
+//! When the `nth` hash parameter is given, the `@nth` local variable is a
+//! boolean indicating whether this is an `nth` repetition (counting from one).
+//! This makes it easy to insert a divider every few rows. For example:
 //!
-//! Like the standard `each` helper function, `repeat` can specify an inverse
-//! block which will be rendered when `count == 0`. For example:
+//! ```notrust
+//! {{#repeat 6 nth=3}}{{@index}}{{#if @nth}} |{{/if}} {{/repeat}}
+//! ```
+//!
+//! Produces:
 //!
 //! ```notrust
-//! {{#repeat 0}}
-//! foo
-//! {{else}}
-//! bar
+//! 0 1 2 | 3 4 5 |
+//! ```
+//!
+//! When the `cycle` hash parameter is given, as either an array or a
+//! comma-separated string, the `@cycle` local variable takes each of its values
+//! in turn, wrapping around as needed. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 cycle="odd,even"}}
+//! <tr class="{{@cycle}}">
 //! {{/repeat}}
 //! ```
 //!
 //! Produces:
 //!
 //! ```notrust
-//! bar
+//! <tr class="odd">
+//! <tr class="even">
+//! <tr class="odd">
 //! ```
 //!
-//! [handlebars]: https://github.com/sunng87/handlebars-rust
-
-#![deny(clippy::all)]
-#![deny(missing_docs)]
-
-use handlebars::*;
-
-/// The `repeat` handler object
-///
-/// To use, register it in your handlebars registry:
-///
-/// ```rust
-/// let mut reg = handlebars::Handlebars::new();
-/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper));
-/// ```
-#[derive(Clone, Copy)]
-pub struct RepeatHelper;
-
-impl HelperDef for RepeatHelper {
-    fn call<'reg: 'rc, 'rc>(
-        &self,
-        h: &Helper<'rc>,
-        r: &'reg Handlebars<'reg>,
-        ctx: &'rc Context,
-        rc: &mut RenderContext<'reg, 'rc>,
-        out: &mut dyn Output,
-    ) -> HelperResult {
-        let value = h
-            .param(0)
-            .ok_or_else(|| RenderErrorReason::ParamNotFoundForIndex("repeat", 0))?
-            .value();
-
-        let count = value.as_u64().ok_or_else(|| {
-            RenderErrorReason::ParamTypeMismatchForName(
-                "repeat",
-                "0".to_string(),
-                "u64".to_string(),
-            )
-        })?;
-
-        let template = h
-            .template()
-            .ok_or_else(|| RenderErrorReason::BlockContentRequired)?;
-
-        for i in 0..count {
-            let mut block = rc.block().cloned().unwrap_or_default();
-            block.set_local_var("index", i.into());
-            block.set_local_var("first", (i == 0).into());
-            block.set_local_var("last", (i == count - 1).into());
-            rc.push_block(block);
-
-            template.render(r, ctx, rc, out)?;
-
-            rc.pop_block();
-        }
-
-        if count == 0 {
-            if let Some(template) = h.inverse() {
-                template.render(r, ctx, rc, out)?;
-            }
-        }
-
-        Ok(())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rstest::*;
-    use serde_json::json;
-
-    const T: &str =
-        "{{#repeat count}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
-
-    #[inline]
-    fn render(template: &str, count: u64) -> Result<String, RenderError> {
-        let data = json!({"name": "foo", "count": count});
-
-        let mut reg = Handlebars::new();
-        reg.register_helper("repeat", Box::new(RepeatHelper));
-        reg.render_template(template, &data)
-    }
-
-    #[rstest]
-    #[case(0, "bar")]
-    #[case(1, "foo:0:true:true ")]
-    #[case(2, "foo:0:true:false foo:1:false:true ")]
-    #[case(3, "foo:0:true:false foo:1:false:false foo:2:false:true ")]
-    fn success(#[case] count: u64, #[case] output: &str) {
-        assert_eq!(render(T, count).unwrap(), output);
-    }
-
-    #[rstest]
-    #[case(0)]
-    #[case(1)]
-    #[case(2)]
-    #[case(3)]
-    fn missing_arg(#[case] count: u64) {
-        let template = "{{#repeat}}{{name}}{{/repeat}}";
-        let err = render(template, count).unwrap_err();
-        assert!(matches!(
-            err.reason(),
-            RenderErrorReason::ParamNotFoundForIndex("repeat", 0)
-        ))
-    }
-
-    #[rstest]
-    #[case(0)]
-    #[case(1)]
-    #[case(2)]
-    #[case(3)]
-    fn wrong_arg_type(#[case] count: u64) {
-        let template = "{{#repeat \"foo\"}}{{name}}{{/repeat}}";
-        let err = render(template, count).unwrap_err();
-        assert!(
-            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
-            "repeat",
-            a,
-            b,
-        ) if (a == &"0".to_string()) && b == &"u64".to_string())
-        )
-    }
-
-    #[rstest]
-    #[case(0)]
-    #[case(1)]
-    #[case(2)]
-    #[case(3)]
-    fn missing_block(#[case] count: u64) {
-        let template = "{{repeat count}}";
-        let err = render(template, count).unwrap_err();
-        assert!(matches!(
-            err.reason(),
-            RenderErrorReason::BlockContentRequired
-        ))
+//! The `@prev` and `@next` local variables are the values of `@index` of the
+//! adjacent repetitions, or `null` at either end. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 start=1}}
+//! Page {{@index}}{{#if @next}} → page {{@next}}{{/if}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Page 1 → page 2
+//! Page 2 → page 3
+//! Page 3
+//! ```
+//!
+//! When the `bucket_size` hash parameter is given, the repetitions are sliced
+//! into buckets of that size. The `@bucket` local variable is the number of
+//! the bucket, counting from zero, while `@bucket_first` and `@bucket_last` are
+//! booleans indicating whether this is the first or last repetition of its
+//! bucket. For example:
+//!
+//! ```notrust
+//! {{#repeat 5 bucket_size=2}}
+//! {{#if @bucket_first}}shard{{@bucket}}:{{/if}} {{@index}}{{#if @bucket_last}};{{/if}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! shard0: 0
+//!  1;
+//! shard1: 2
+//!  3;
+//! shard2: 4;
+//! ```
+//!
+//! When the `sep` hash parameter is given, the `@sep` local variable is its
+//! value on every repetition but the last, where it is empty. Unlike the
+//! `separator` hash parameter, this permits the separator to be placed anywhere
+//! within the block. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 sep=","}}
+//! {{@index}}{{@sep}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0,
+//! 1,
+//! 2
+//! ```
+//!
+//! Where the separator calls for expressions or line breaks, the `sep` block
+//! helper (see [`SepHelper`]) renders its block on every repetition but the
+//! last:
+//!
+//! ```notrust
+//! {{#repeat 3}}
+//! {{@index}}
+//! {{#sep}}
+//! ---- {{@index1}} ----
+//! {{/sep}}
+//! {{/repeat}}
+//! ```
+//!
+//! When the `format` hash parameter is given, the `@index_fmt` local variable
+//! is `@index` formatted by a printf-style format containing one conversion:
+//! `%d` for decimal, `%x` or `%X` for hexadecimal, `%o` for octal or `%b` for
+//! binary, optionally preceded by a `0` flag and a width of at most 64. For
+//! example:
+//!
+//! ```notrust
+//! {{#repeat 3 step=4 format="REG_%02X"}}
+//! #define {{@index_fmt}} {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! #define REG_00 0
+//! #define REG_04 4
+//! #define REG_08 8
+//! ```
+//!
+//! With the `i18n` feature enabled, the `@index_loc` local variable is
+//! `@index` with its digits grouped as is customary for the locale named by
+//! the `locale` hash parameter (default: `en`). For example, a `locale` of
+//! `en` gives `1,000`, `de` gives `1.000` and `fr` gives `1 000`.
+//!
+//! The `@hash` local variable is a hexadecimal digest of `@index` and the
+//! `seed` hash parameter (default: zero). It is stable from one render to the
+//! next, which makes it suitable for element ids and cache keys:
+//!
+//! ```notrust
+//! {{#repeat 2 seed=7}}
+//! <section id="s-{{@hash}}"></section>
+//! {{/repeat}}
+//! ```
+//!
+//! When the `counter` hash parameter is given, the `@counter` local variable
+//! is the next value of the named counter, counting from one. The counter is
+//! shared by every block of the render naming it, and by the `counter` helper
+//! (see [`CounterHelper`]), which advances it and writes its value. This gives
+//! continuous numbering across separate blocks, and across the partials and
+//! `escape` or `on_error` blocks within them. A partial renders with a copy of
+//! the render context, however, so a partial called at the top level of a
+//! template, before any block or `counter` helper there, keeps its counters
+//! only until it ends. Call such partials within a block, or advance a counter
+//! before them, to number them continuously. For example:
+//!
+//! ```notrust
+//! {{#repeat 2 counter="fig"}}Figure {{@counter}}. {{/repeat}}
+//! Figure {{counter "fig"}}.
+//! {{#repeat 1 counter="fig"}}Figure {{@counter}}.{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Figure 1. Figure 2.
+//! Figure 3.
+//! Figure 4.
+//! ```
+//!
+//! The `label` hash parameter names a block, whose local variables are then
+//! available within it and within any nested `repeat` blocks, however deeply
+//! nested, as flattened local variables such as `@row_index`. The innermost
+//! block of a given label takes precedence. Handlebars reads `{{@row.index}}`
+//! as `@row` alone, so the dotted form is not available, but `@row` is also
+//! an object whose members may be read with `lookup`, as for `@outer`. For
+//! example:
+//!
+//! ```notrust
+//! {{#repeat 2 label="row"}}
+//! {{#repeat 2 label="col"}}({{@row_index}},{{lookup @col "index"}}){{/repeat}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! (0,0)(0,1)
+//! (1,0)(1,1)
+//! ```
+//!
+//! The names of the local variables may be changed for every block with
+//! `RepeatHelper::with_local_prefix` and `RepeatHelper::with_local_name`, for
+//! example to avoid shadowing those of an enclosing `each` block.
+//!
+//! ## Block Parameters
+//!
+//! Like the standard `each` helper function, `repeat` supports a block
+//! parameter which is bound to the value of `@index`. This is useful in nested
+//! blocks where `@index` is shadowed. For example:
+//!
+//! ```notrust
+//! {{#repeat 2 as |i|}}
+//! {{#repeat 2}}
+//! {{i}}.{{@index}}
+//! {{/repeat}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0.0
+//! 0.1
+//! 1.0
+//! 1.1
+//! ```
+//!
+//! A second block parameter is bound to the `count`. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 as |i n|}}
+//! Item {{i}} of {{n}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Item 0 of 3
+//! Item 1 of 3
+//! Item 2 of 3
+//! ```
+//!
+//! ## Starting Index
+//!
+//! By default, `@index` starts at zero. The `start` hash parameter offsets it
+//! to begin at any other value. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 start=10}}
+//! Index: {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Index: 10
+//! Index: 11
+//! Index: 12
+//! ```
+//!
+//! For human-facing numbering, `base` is accepted as an alias of `start`, so
+//! `base=1` makes `@index` count from one. If both are given, `start` takes
+//! precedence. A `start` which would carry the last `@index` beyond the range
+//! of a `u64` is an error.
+//!
+//! ## Step
+//!
+//! The `step` hash parameter changes the distance between successive values
+//! of `@index`. The `count` still determines the number of repetitions. For
+//! example:
+//!
+//! ```notrust
+//! {{#repeat 3 step=2}}
+//! Index: {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Index: 0
+//! Index: 2
+//! Index: 4
+//! ```
+//!
+//! The `start` and `step` parameters may be combined. As with `start`, a
+//! `step` which would carry the last `@index` beyond the range of a `u64` is
+//! an error.
+//!
+//! ## Geometric Progressions
+//!
+//! The `multiply` hash parameter exposes a `@value` variable which starts at
+//! `start` and is multiplied by the given factor on each repetition, while
+//! `@index` continues to grow linearly. For example:
+//!
+//! ```notrust
+//! {{#repeat 4 start=1 multiply=2}}
+//! Attempt {{@index}}: wait {{@value}}s
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Attempt 1: wait 1s
+//! Attempt 2: wait 2s
+//! Attempt 3: wait 4s
+//! Attempt 4: wait 8s
+//! ```
+//!
+//! Without a `start` (or `base`, or the start of a range), `@value` begins at
+//! one rather than zero, so that `{{#repeat 4 multiply=2}}` gives 1, 2, 4 and
+//! 8 while `@index` still counts from zero. A value which does not fit in a
+//! `u64` produces an error.
+//!
+//! ## Ranges
+//!
+//! When given two parameters, `repeat` iterates over the range from the first
+//! (inclusive) to the second (exclusive), honoring `step`. The `@first` and
+//! `@last` variables reflect the bounds of the range. For example:
+//!
+//! ```notrust
+//! {{#repeat 2 5}}
+//! Index: {{@index}} (first: {{@first}}; last: {{@last}})
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Index: 2 (first: true; last: false)
+//! Index: 3 (first: false; last: false)
+//! Index: 4 (first: false; last: true)
+//! ```
+//!
+//! Setting the `inclusive` hash parameter to `true` includes the second
+//! parameter in the range, so `{{#repeat 1 5 inclusive=true}}` repeats for the
+//! values 1 through 5. An empty range renders the inverse block.
+//!
+//! The `range` helper (see [`RangeHelper`]) is a sibling of `repeat` for such
+//! ranges, taking an optional third parameter as the step and rendering each
+//! repetition with its `@index` as the context. As a subexpression, it returns
+//! the array of values instead, as in `{{#each (range 0 5)}}`.
+//!
+//! ```notrust
+//! {{#range 1 10 3}}
+//! pin {{this}}{{#if @last}} (last){{/if}}
+//! {{/range}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! pin 1
+//! pin 4
+//! pin 7 (last)
+//! ```
+//!
+//! The `seq` helper (see [`SeqHelper`]) follows `seq(1)` instead, with both
+//! bounds inclusive and an increment, possibly negative, between them. With a
+//! printf-style `format`, it maps directly onto shell and Makefile idioms:
+//!
+//! ```notrust
+//! {{#seq 3 -1 1 format="step%02d"}}
+//! {{this}}: ; ./run {{@value}}
+//! {{/seq}}
+//! ```
+//!
+//! Likewise, the `countdown` helper (see [`CountdownHelper`]) counts down to
+//! one, so that `{{#countdown 3}}` renders with the values 3, 2 and 1.
+//!
+//! Sequences which follow from the value before, such as backoff schedules,
+//! use the `unfold` helper (see [`UnfoldHelper`]), which evaluates its `next`
+//! expression for each value in turn:
+//!
+//! ```notrust
+//! {{#unfold seed=100 next="mul @value 2" count=4}}
+//! retry after {{this}}ms
+//! {{/unfold}}
+//! ```
+//!
+//! Characters have their own ranges: the `chars` helper (see [`CharsHelper`])
+//! iterates the characters between two bounds, as the `@char` local variable,
+//! and the `alphabet` helper (see [`AlphabetHelper`]) the letters of the
+//! alphabet, as for column labels:
+//!
+//! ```notrust
+//! {{#chars "A" "D"}}<th>{{@char}}</th>{{/chars}}
+//! ```
+//!
+//! The `hours` helper (see [`HoursHelper`]) iterates the times of day between
+//! two hours, by a `step` which may be fractional, with the time as the `@hour`
+//! local variable alongside those of `repeat`:
+//!
+//! ```notrust
+//! {{#hours from=9 to=17 step=0.5}}
+//! <tr class="{{@parity}}"><td>{{@hour}}</td></tr>
+//! {{/hours}}
+//! ```
+//!
+//! Likewise, the `every` helper (see [`EveryHelper`]) iterates the offsets of
+//! a duration, such as `15m` or `1h30m`, up to a `for` duration, as the
+//! `@offset` and `@seconds` local variables:
+//!
+//! ```notrust
+//! {{#every "30s" for="5m"}}
+//! - at: "{{@offset}}"
+//! {{/every}}
+//! ```
+//!
+//! Storage layouts use the `blocks` helper (see [`BlocksHelper`]), which
+//! iterates the blocks of a byte size, such as `4KiB`, with the `@offset` and
+//! `@len` of each; the final block is shorter when the size is not a multiple:
+//!
+//! ```notrust
+//! {{#blocks "1MiB" block="64KiB"}}
+//! sector {{@index}} at {{@offset}}, {{@len}} bytes
+//! {{/blocks}}
+//! ```
+//!
+//! The `offsets` helper (see [`OffsetsHelper`]) is alike, but gives the
+//! `@offset` in hexadecimal for hexdump layouts and memory maps:
+//!
+//! ```notrust
+//! {{#offsets total=256 width=16}}
+//! {{@offset}}: {{#repeat @len}}.. {{/repeat}}
+//! {{/offsets}}
+//! ```
+//!
+//! More generally, the `chunks` helper (see [`ChunksHelper`]) splits a `total`
+//! into chunks of a `size`, with the `@offset` and `@len` of each, the last
+//! being short as needed:
+//!
+//! ```notrust
+//! {{#chunks total=rows size=500}}
+//! SELECT * FROM t LIMIT {{@len}} OFFSET {{@offset}};
+//! {{/chunks}}
+//! ```
+//!
+//! Finally, the `digits` helper (see [`DigitsHelper`]) iterates the decimal
+//! digits of a number, as the `@digit` local variable alongside those of
+//! `repeat`, optionally padded to a `width`:
+//!
+//! ```notrust
+//! {{#digits odometer width=6}}<img src="/digits/{{@digit}}.png">{{/digits}}
+//! ```
+//!
+//! ## Reverse
+//!
+//! Setting the `reverse` hash parameter to `true` produces the same values of
+//! `@index` in the opposite order. The `@first` and `@last` variables follow
+//! the order of rendering. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 reverse=true}}
+//! Index: {{@index}} (first: {{@first}}; last: {{@last}})
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Index: 2 (first: true; last: false)
+//! Index: 1 (first: false; last: false)
+//! Index: 0 (first: false; last: true)
+//! ```
+//!
+//! ## Separator
+//!
+//! The `separator` hash parameter is written between repetitions, but not
+//! before the first or after the last. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 separator=", "}}{{@index}}{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0, 1, 2
+//! ```
+//!
+//! The `last_separator` hash parameter, if given, is written between the last
+//! two repetitions instead. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 separator=", " last_separator=", and "}}{{@index}}{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0, 1, and 2
+//! ```
+//!
+//! ## Wrapping
+//!
+//! The `wrap_every` hash parameter groups the repetitions, writing the
+//! `wrap_open` string before each group and the `wrap_close` string after it,
+//! including after a final partial group. This is useful for rows of tables
+//! and grids. For example:
+//!
+//! ```notrust
+//! {{#repeat 5 wrap_every=2 wrap_open="<tr>" wrap_close="</tr>"}}<td>{{@index}}</td>{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! <tr><td>0</td><td>1</td></tr><tr><td>2</td><td>3</td></tr><tr><td>4</td></tr>
+//! ```
+//!
+//! For full control over the groups, the `group_size` hash parameter instead
+//! exposes the `@group_index` local variable, the number of the group counting
+//! from zero, along with the `@group_first` and `@group_last` booleans. Unlike
+//! buckets, which slice the indices, groups follow the order of rendering and
+//! so begin with the first repetition rendered, whatever the `skip` or
+//! `reverse` hash parameters. For example:
+//!
+//! ```notrust
+//! {{#repeat 5 group_size=2}}
+//! {{#if @group_first}}<tr class="row{{@group_index}}">{{/if}}
+//! <td>{{@index}}</td>
+//! {{#if @group_last}}</tr>{{/if}}
+//! {{/repeat}}
+//! ```
+//!
+//! ## Trimming
+//!
+//! Block templates written for readability usually begin and end with a line
+//! break. The `chomp` hash parameter (or its alias `trim`) strips leading and
+//! trailing line breaks from the output of each repetition. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 chomp=true separator=", "}}
+//!   {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//!   0,   1,   2
+//! ```
+//!
+//! ## Indentation
+//!
+//! The `indent` hash parameter prefixes every non-empty line written by the
+//! block, including lines written by partials, which is useful when
+//! generating indentation-sensitive formats such as YAML or Python. It is
+//! either a number of spaces, of at most 1024, or a string. For example:
+//!
+//! ```notrust
+//! items:
+//! {{#repeat 2 indent=2}}
+//! - name: item{{@index}}
+//!   value: {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! items:
+//!   - name: item0
+//!     value: 0
+//!   - name: item1
+//!     value: 1
+//! ```
+//!
+//! ## Framing
+//!
+//! The `prefix` hash parameter is written before the first repetition and the
+//! `suffix` hash parameter after the last, while the `terminator` hash
+//! parameter is written after every repetition. None of them are written when
+//! nothing is repeated. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 prefix="int x[] = {" separator=", " suffix="};"}}{{@index}}{{/repeat}}
+//! {{#repeat 2 terminator=";"}}SELECT {{@index}}{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! int x[] = {0, 1, 2};
+//! SELECT 0;SELECT 1;
+//! ```
+//!
+//! ## Windows
+//!
+//! The `skip` and `limit` hash parameters render only a window of the
+//! repetitions, such as a page of a longer list. The `@index` variable keeps
+//! its absolute value, while `@first`, `@last` and `@remaining` refer to the
+//! window. The `@rindex`, `@count`, `@progress` and `@percent` variables still
+//! refer to all of the repetitions. For example:
+//!
+//! ```notrust
+//! {{#repeat 100 skip=20 limit=3}}
+//! Index: {{@index}} (first: {{@first}}; last: {{@last}})
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! Index: 20 (first: true; last: false)
+//! Index: 21 (first: false; last: false)
+//! Index: 22 (first: false; last: true)
+//! ```
+//!
+//! The inverse block is only rendered when the `count` itself is zero.
+//!
+//! ## Raw Blocks
+//!
+//! Setting the `raw` hash parameter to `true` writes the literal text of the
+//! block on each repetition without evaluating it or building its local
+//! variables, which is faster for banners and padding. A raw block which
+//! contains an expression produces an error, guaranteeing that nothing is
+//! interpolated. For example:
+//!
+//! ```notrust
+//! {{#repeat 3 raw=true}}=-{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! =-=-=-
+//! ```
+//!
+//! ## Escaping
+//!
+//! The `escape` hash parameter overrides the registry's escape function within
+//! the block: `html` escapes for HTML, `json` escapes for the inside of a JSON
+//! string and `none` disables escaping. For example, given `{"name": "\"x\""}`:
+//!
+//! ```notrust
+//! {"names": [{{#repeat 2 escape="json" separator=", "}}"{{name}}"{{/repeat}}]}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! {"names": ["\"x\"", "\"x\""]}
+//! ```
+//!
+//! The `html` and `json` modes apply to the expressions and helpers of the
+//! block, and to those of the partials it names, however deeply. A partial
+//! chosen by a subexpression, such as `{{> (name)}}`, keeps the registry's
+//! escape function.
+//!
+//! ## Output Budget
+//!
+//! The `max_bytes` hash parameter stops repeating once the block has written at
+//! least that many bytes, guarding against runaway output. When repetition
+//! stops early, the `truncation` hash parameter, if given, is written in place
+//! of the remaining repetitions. For example:
+//!
+//! ```notrust
+//! {{#repeat 1000 max_bytes=10 truncation="..."}}{{@index}} {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 0 1 2 3 4 ...
+//! ```
+//!
+//! ## Stopping Early
+//!
+//! The `until` hash parameter gives a path which is evaluated before each
+//! repetition, within the context of that repetition. Once it is truthy, no
+//! further repetitions are rendered. The `count` still bounds the number of
+//! repetitions, so there is no risk of an infinite loop. For example, given
+//! `{"tasks": [{"name": "a"}, {"name": "b", "done": true}, {"name": "c"}]}`:
+//!
+//! ```notrust
+//! {{#repeat tasks until="done"}}
+//! {{name}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! a
+//! ```
+//!
+//! Local variables may also be used, as in `until="@last"`. Note that `@last`
+//! reflects the planned repetitions, and so is never true when the loop stops
+//! early.
+//!
+//! The `until` parameter may also be a subexpression, given as a string since
+//! handlebars would otherwise evaluate it once, before the first repetition.
+//! The parentheses may be left out, as in `until="gt @index budget"`, which,
+//! like `until="(gt @index budget)"`, is evaluated again for each repetition.
+//!
+//! The `break` helper (see [`BreakHelper`]) stops from within the block
+//! itself: the rest of the repetition is abandoned and no more are rendered.
+//!
+//! ```notrust
+//! {{#repeat 10}}
+//! {{#if (gt @index budget)}}{{break}}{{/if}}
+//! item {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Similarly, the `continue` helper (see [`ContinueHelper`]) abandons the rest
+//! of the repetition and moves on to the next, sparing the block a wrapping
+//! `if`:
+//!
+//! ```notrust
+//! {{#repeat 10}}
+//! {{#if (eq @index skipped)}}{{continue}}{{/if}}
+//! item {{@index}}
+//! {{/repeat}}
+//! ```
+//!
+//! Loops without a count in advance use the `while` helper (see
+//! [`WhileHelper`]), which evaluates its condition again before each
+//! repetition and requires a `max` cap:
+//!
+//! ```notrust
+//! {{#while "lookup jobs @index" max=1000}}
+//! job {{@index}}: {{lookup jobs @index}}
+//! {{/while}}
+//! ```
+//!
+//! ## Error Handling
+//!
+//! By default, a repetition which fails to render fails the whole render. The
+//! `on_error` hash parameter instead renders the other repetitions: `skip`
+//! omits the failed repetition, `stop` ends the repetition quietly and `inline`
+//! writes the `placeholder` hash parameter, or else the error message, escaped
+//! as the block's expressions are, in its place. For example, given
+//! `{"values": [1, 0, 2]}` and a helper which fails on zero:
+//!
+//! ```notrust
+//! {{#repeat values on_error="inline" placeholder="n/a" separator=", "}}{{check this}}{{/repeat}}
+//! {{#repeat values on_error="skip" separator=", "}}{{check this}}{{/repeat}}
+//! {{#repeat values on_error="stop" separator=", "}}{{check this}}{{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 1, n/a, 2
+//! 1, 2
+//! 1
+//! ```
+//!
+//! Since a failed repetition must write nothing, each repetition is buffered
+//! before being written.
+//!
+//! ## Maximum
+//!
+//! When rendering untrusted data, the `max` hash parameter caps the number of
+//! repetitions. A larger count fails with `Error::TooManyRepetitions`, unless
+//! `clamp=true` is also given, in which case only `max` repetitions are
+//! rendered. For example:
+//!
+//! ```notrust
+//! {{#repeat 1000000 max=3 clamp=true}}
+//! hi
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! hi
+//! hi
+//! hi
+//! ```
+//!
+//! Registry-wide limits which template authors cannot override may be set with
+//! `RepeatHelper::with_max_count`.
+//!
+//! ## Random Counts
+//!
+//! With the `rand` feature enabled, `random=true` picks a pseudo-random count
+//! between the `min` (default: zero) and `max` hash parameters, inclusive. The
+//! choice is fully determined by the `seed` hash parameter (default: zero),
+//! which makes it suitable for generating reproducible test fixtures:
+//!
+//! ```notrust
+//! {{#repeat random=true min=2 max=8 seed=42}}
+//! row
+//! {{/repeat}}
+//! ```
+//!
+//! The feature also exposes the `@random` local variable: a pseudo-random
+//! number from zero up to (but excluding) one. It is determined by the `seed`
+//! hash parameter and the index, so every repetition gets its own value which
+//! stays the same from one render to the next:
+//!
+//! ```notrust
+//! {{#repeat 3 seed=7}}
+//! price: {{@random}}
+//! {{/repeat}}
+//! ```
+//!
+//! ## UUIDs
+//!
+//! With the `uuid` feature enabled, the `@uuid` local variable holds a version
+//! 4 UUID which is fresh for every repetition of every render. When the
+//! `uuid_seed` hash parameter is given, the UUIDs are instead derived from it
+//! and the index, so that the output is reproducible:
+//!
+//! ```notrust
+//! {{#repeat 2 uuid_seed=1}}
+//! - id: {{@uuid}}
+//! {{/repeat}}
+//! ```
+//!
+//! The `uuid` feature enables the `rand` feature, whose SplitMix64 generator
+//! also gives the fresh UUIDs. It is seeded once for each process from the
+//! random keys of the standard library's hash maps, and its sequence repeats
+//! only after 2^64 numbers, so the fresh UUIDs of a process are distinct except
+//! with the odds of 122 random bits colliding. Across processes, they are as
+//! distinct as the 64-bit seeds. These UUIDs are predictable from one another,
+//! and so are not suitable for cryptographic purposes or as secrets.
+//!
+//! ## Dates
+//!
+//! With the `time` feature enabled, the `dates` helper (see `DatesHelper`)
+//! repeats its block for each day between two dates, or for each step of days,
+//! weeks, months or years. The `@date` local variable holds the date, in the
+//! `format` given, and `@weekday` its day of the week:
+//!
+//! ```notrust
+//! {{#dates from="2024-01-01" to="2024-01-31" step="1w" format="%a %d %b"}}
+//! | {{@date}} | {{#if (eq @weekday "Monday")}}standup{{/if}} |
+//! {{/dates}}
+//! ```
+//!
+//! The `business_days` helper (see `BusinessDaysHelper`) skips the weekends,
+//! as well as the dates of the `holidays` hash parameter:
+//!
+//! ```notrust
+//! {{#business_days from=opened to=today holidays=holidays}}
+//! {{#if @last}}{{@index}} business days open{{/if}}
+//! {{/business_days}}
+//! ```
+//!
+//! Likewise, the `months` helper (see `MonthsHelper`) repeats its block for
+//! each month between two months, with the `@year`, `@month` and `@month_name`
+//! local variables. With the `i18n` feature also enabled, the `locale` hash
+//! parameter gives the language of the month names:
+//!
+//! ```notrust
+//! {{#months from="2024-01" to="2024-12" locale="de"}}
+//! <a href="/archive/{{this}}">{{@month_name}} {{@year}}</a>
+//! {{/months}}
+//! ```
+//!
+//! The `weeks` helper (see `WeeksHelper`) repeats its block for each ISO 8601
+//! week of a `year`, or of the range between two dates, with the `@week`,
+//! `@start_date` and `@end_date` local variables:
+//!
+//! ```notrust
+//! {{#weeks year=2025}}
+//! Sprint {{@week}}: {{@start_date}} – {{@end_date}}
+//! {{/weeks}}
+//! ```
+//!
+//! Finally, the `calendar` helper (see `CalendarHelper`) repeats its block for
+//! each day of the grid of a month, whole weeks included, with the `@day`,
+//! `@in_month` and `@weekday` local variables and the `@week_start` and
+//! `@week_end` boundaries of the weeks:
+//!
+//! ```notrust
+//! <table>
+//! {{#calendar year=2025 month=6}}
+//! {{#if @week_start}}<tr>{{/if}}
+//! <td{{#unless @in_month}} class="other"{{/unless}}>{{@day}}</td>
+//! {{#if @week_end}}</tr>{{/if}}
+//! {{/calendar}}
+//! </table>
+//! ```
+//!
+//! ## Sections
+//!
+//! The `{{else before}}` section is rendered once before the repetitions, with
+//! the `@count` local variable holding the count. Likewise, the `{{else after}}`
+//! section is rendered once after them, with `@rendered` also holding the
+//! number of repetitions actually rendered. Neither is rendered when the count
+//! is zero, which suits table headers, footers and summaries:
+//!
+//! ```notrust
+//! {{#repeat 3}}
+//! row {{@index}}
+//! {{else before}}
+//! {{@count}} rows
+//! {{else after}}
+//! {{@rendered}} of {{@count}} rows shown
+//! {{else}}
+//! no rows
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 3 rows
+//! row 0
+//! row 1
+//! row 2
+//! 3 of 3 rows shown
+//! ```
+//!
+//! When the count is one, the `{{else single}}` section is rendered in place
+//! of the block, so that a lone item may read differently from a list:
+//!
+//! ```notrust
+//! {{#repeat count}}
+//! - item {{@index1}}
+//! {{else single}}
+//! just the one item
+//! {{/repeat}}
+//! ```
+//!
+//! Sections must come before the plain `{{else}}` of the inverse block.
+//!
+//! ## Accumulators
+//!
+//! The `acc` helper (see [`AccHelper`]) keeps running totals for the innermost
+//! `repeat` block. Within the block, `{{acc "total" price}}` adds to the named
+//! accumulator, while `{{acc "total"}}` writes its value so far. Afterwards,
+//! `{{acc "total"}}` writes its final value, which the `{{else after}}`
+//! section also receives as an entry of the `@acc` local variable. For
+//! example, given `{"items": [{"price": 3}, {"price": 4}]}`:
+//!
+//! ```notrust
+//! {{#repeat items}}
+//! {{acc "total" price}}
+//! {{price}} (subtotal {{acc "total"}})
+//! {{else after}}
+//! total {{lookup @acc "total"}}
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 3 (subtotal 3)
+//! 4 (subtotal 7)
+//! total 7
+//! ```
+//!
+//! With `collect=true`, values of any kind are collected into an array rather
+//! than summed.
+//!
+//! ## Partial Blocks
+//!
+//! Within a partial called with a block, `repeat` may render the
+//! `@partial-block` on every repetition, composing layouts such as:
+//!
+//! ```notrust
+//! {{#*inline "grid"}}{{#repeat 3}}<td>{{> @partial-block}}</td>{{/repeat}}{{/inline}}
+//! {{#> grid}}cell {{@index}}{{/grid}}
+//! ```
+//!
+//! The partial block sees the local variables of the repetition.
+//!
+//! ## Subexpressions
+//!
+//! As a subexpression, `repeat` returns the array of the values `@index` would
+//! take, in the order of rendering, so that `(repeat 3)` is `[0, 1, 2]`. This
+//! may feed other helpers, such as `each` or `lookup`:
+//!
+//! ```notrust
+//! {{#each (repeat 3 start=1)}}{{this}} {{/each}}
+//! {{lookup (repeat 5 step=10) 2}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! 1 2 3
+//! 20
+//! ```
+//!
+//! ## Decorator
+//!
+//! The `*repeat` decorator (see [`RepeatDecorator`]) renders nothing, instead
+//! setting a key of the current context to an array of the given length, read
+//! as a count is. The elements are their indices, or else the `fill` hash
+//! parameter:
+//!
+//! ```notrust
+//! {{*repeat 3 name="slots" fill="empty"}}
+//! {{#each slots}}{{@index}}: {{this}}
+//! {{/each}}
+//! ```
+//!
+//! ## Sequence Helpers
+//!
+//! The helpers which repeat over a sequence of their own, such as `seq`,
+//! `chars`, `hours`, `digits`, `blocks` and `every`, and, with the `time`
+//! feature, `dates`, `weeks`, `months` and `calendar`, render each item as
+//! `repeat` renders a repetition. Besides its own local variables, an item has
+//! those of `repeat` which follow from its position, such as `@index`, `@last`,
+//! `@roman` or `@rindex`, and the `sep`, `break` and `acc` helpers act upon
+//! the block of the helper:
+//!
+//! ```notrust
+//! {{#seq 3}}{{@roman}}{{#sep}}, {{/sep}}{{/seq}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! i, ii, iii
+//! ```
+//!
+//! ## Inverse Block
+//!
+//! Like the standard `each` helper function, `repeat` can specify an inverse
+//! block which will be rendered when `count == 0`. For example:
+//!
+//! ```notrust
+//! {{#repeat 0}}
+//! foo
+//! {{else}}
+//! bar
+//! {{/repeat}}
+//! ```
+//!
+//! Produces:
+//!
+//! ```notrust
+//! bar
+//! ```
+//!
+//! The inverse block may be chained, as with the standard `if` helper, in which
+//! case the whole chain is evaluated when `count == 0`:
+//!
+//! ```notrust
+//! {{#repeat items}}
+//! item
+//! {{else if fallback}}
+//! {{fallback}}
+//! {{else}}
+//! nothing
+//! {{/repeat}}
+//! ```
+//!
+//! [handlebars]: https://github.com/sunng87/handlebars-rust
+
+#![deny(clippy::all)]
+#![deny(missing_docs)]
+
+mod bytes;
+#[cfg(feature = "time")]
+mod calendar;
+mod chars;
+mod companions;
+mod condition;
+#[cfg(feature = "time")]
+mod date;
+#[cfg(feature = "time")]
+mod dates;
+mod decorator;
+mod digits;
+mod each;
+mod escape;
+mod every;
+mod expression;
+mod format;
+mod frames;
+mod hours;
+#[cfg(feature = "i18n")]
+mod locale;
+mod locals;
+#[cfg(feature = "time")]
+mod months;
+mod numerals;
+mod output;
+mod rand;
+mod range;
+mod sections;
+mod seq;
+mod shared;
+mod unfold;
+#[cfg(feature = "uuid")]
+mod uuid;
+#[cfg(feature = "time")]
+mod weeks;
+
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use handlebars::template::TemplateElement;
+use handlebars::*;
+
+pub use bytes::{BlocksHelper, ChunksHelper, OffsetsHelper};
+#[cfg(feature = "time")]
+pub use calendar::CalendarHelper;
+pub use chars::{AlphabetHelper, CharsHelper};
+pub use companions::{AccHelper, BreakHelper, ContinueHelper, SepHelper};
+pub use condition::WhileHelper;
+#[cfg(feature = "time")]
+pub use dates::{BusinessDaysHelper, DatesHelper};
+pub use decorator::RepeatDecorator;
+pub use digits::DigitsHelper;
+pub use every::EveryHelper;
+pub use hours::HoursHelper;
+#[cfg(feature = "time")]
+pub use months::MonthsHelper;
+pub use range::RangeHelper;
+pub use seq::{CountdownHelper, SeqHelper};
+pub use shared::CounterHelper;
+pub use unfold::UnfoldHelper;
+#[cfg(feature = "time")]
+pub use weeks::WeeksHelper;
+
+/// The policy for numeric parameters given as fractional numbers
+///
+/// Counts computed upstream sometimes arrive as floating point numbers, such
+/// as `3.0` or `2.5`. Floats with no fractional part are always accepted; this
+/// policy decides what happens to the rest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Reject numbers with a fractional part (the default)
+    #[default]
+    Reject,
+
+    /// Discard the fractional part
+    Truncate,
+
+    /// Round to the nearest integer, with halves rounded away from zero
+    Round,
+
+    /// Round up to the next integer
+    Ceil,
+}
+
+/// The policy for negative counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Negative {
+    /// Fail with `Error::NegativeCount` (the default)
+    #[default]
+    Error,
+
+    /// Clamp the count to zero, rendering neither the block nor its inverse
+    Clamp,
+
+    /// Render the inverse block, as for a count of zero
+    Inverse,
+}
+
+/// Errors raised by the `repeat` helper
+///
+/// These are reported as [`RenderErrorReason::NestedError`] and may be
+/// recovered with `downcast_ref`.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The count was negative
+    NegativeCount(f64),
+
+    /// The count exceeded the maximum number of repetitions
+    TooManyRepetitions {
+        /// The requested number of repetitions
+        count: u64,
+
+        /// The maximum number of repetitions
+        max: u64,
+    },
+
+    /// The `@value` of the given repetition does not fit in a `u64`
+    ValueOverflow(u64),
+
+    /// The `@index` of the given repetition does not fit in a `u64`
+    IndexOverflow(u64),
+
+    /// The block of a `raw` repetition contains an expression
+    RawExpression,
+
+    /// The named companion helper was used outside of a `repeat` block
+    OutsideRepeat(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeCount(count) => write!(f, "repeat count {} is negative", count),
+            Self::TooManyRepetitions { count, max } => {
+                write!(f, "repeat count {} exceeds the maximum of {}", count, max)
+            }
+            Self::ValueOverflow(i) => write!(f, "repeat value overflows at repetition {}", i),
+            Self::IndexOverflow(i) => write!(f, "repeat index overflows at repetition {}", i),
+            Self::RawExpression => write!(f, "raw repeat block contains an expression"),
+            Self::OutsideRepeat(name) => write!(f, "{} helper used outside a repeat block", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for RenderError {
+    fn from(error: Error) -> Self {
+        RenderErrorReason::NestedError(Box::new(error)).into()
+    }
+}
+
+/// The greatest number of spaces which `indent` may give
+const MAX_INDENT: u64 = 1024;
+
+/// The unit in which a string is measured by `len`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Length {
+    Chars,
+    Bytes,
+}
+
+/// The repetitions planned by the parameters
+struct Plan<'a, 'rc> {
+    scope: Option<Scope<'a, 'rc>>,
+    start: u64,
+    step: u64,
+    count: u64,
+}
+
+/// The context of the repeated block, when it differs from the current one
+enum Scope<'a, 'rc> {
+    /// Each repetition is bound to the corresponding element of an array
+    Array(&'a PathAndJson<'rc>, &'a [JsonValue]),
+
+    /// Each repetition is bound to the same value
+    Value(JsonValue),
+}
+
+/// The escaping applied within the repeated block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Escape {
+    Html,
+    None,
+    Json,
+}
+
+/// The handling of a repetition which fails to render
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OnError {
+    Skip,
+    Stop,
+    Inline,
+}
+
+/// Renders a template, with escaping disabled if need be
+fn render<'reg: 'rc, 'rc>(
+    template: &'rc Template,
+    r: &'reg Handlebars<'reg>,
+    escape: Option<Escape>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    match escape {
+        Some(Escape::None) => {
+            let disabled = rc.is_disable_escape();
+            rc.set_disable_escape(true);
+            let result = template.render(r, ctx, rc, out);
+            rc.set_disable_escape(disabled);
+            result
+        }
+
+        _ => template.render(r, ctx, rc, out),
+    }
+}
+
+/// The local variable marking the block of a repetition with the depth of its
+/// frame
+const GUARD: &str = "repeat-guard";
+
+/// Renders a repetition, restoring the render context should it fail
+///
+/// A helper which fails may leave its own blocks pushed, or escaping disabled,
+/// so these are unwound back to the block of the repetition.
+fn attempt<'reg: 'rc, 'rc>(
+    template: &'rc Template,
+    r: &'reg Handlebars<'reg>,
+    escape: Option<Escape>,
+    depth: u64,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let disabled = rc.is_disable_escape();
+    let result = render(template, r, escape, ctx, rc, out);
+
+    if result.is_err() {
+        let guard = JsonValue::from(depth);
+        while matches!(rc.block(), Some(block) if block.get_local_var(GUARD) != Some(&guard)) {
+            rc.pop_block();
+        }
+
+        rc.set_disable_escape(disabled);
+    }
+
+    result
+}
+
+/// Escapes text written by the block itself, as its expressions are escaped
+fn escaped(
+    text: &str,
+    escape: Option<Escape>,
+    r: &Handlebars<'_>,
+    rc: &RenderContext<'_, '_>,
+) -> String {
+    match escape {
+        Some(Escape::Html) => html_escape(text),
+        Some(Escape::Json) => escape::json(text),
+        Some(Escape::None) => text.into(),
+        None if rc.is_disable_escape() => text.into(),
+        None => r.get_escape_fn()(text),
+    }
+}
+
+/// Renders a section of the block once, with the given local variables
+#[allow(clippy::too_many_arguments)]
+fn section<'reg: 'rc, 'rc>(
+    template: &'rc Template,
+    frame: &frames::Frame,
+    locals: JsonValue,
+    r: &'reg Handlebars<'reg>,
+    escape: Option<Escape>,
+    ctx: &'rc Context,
+    rc: &mut RenderContext<'reg, 'rc>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let mut block = rc.block().cloned().unwrap_or_default();
+    frame.set(&mut block, None, Some(locals.clone()));
+    if let JsonValue::Object(locals) = locals {
+        for (name, value) in locals {
+            block.set_local_var(&name, value);
+        }
+    }
+
+    rc.push_block(block);
+    let result = render(template, r, escape, ctx, rc, out);
+    rc.pop_block();
+    companions::catch(result, &mut None)
+}
+
+/// Resolves a dotted path, such as `config.replicas`, within a value
+fn lookup<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+    path.split('.').try_fold(value, |value, key| match value {
+        JsonValue::Object(object) => object.get(key),
+        JsonValue::Array(array) => array.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+fn hash<'a, T>(
+    h: &'a Helper<'_>,
+    name: &str,
+    kind: &str,
+    convert: impl FnOnce(&'a JsonValue) -> Option<T>,
+) -> Result<Option<T>, RenderError> {
+    h.hash_get(name)
+        .map(|value| {
+            convert(value.value()).ok_or_else(|| {
+                RenderErrorReason::HashTypeMismatchForName(
+                    "repeat",
+                    name.to_string(),
+                    kind.to_string(),
+                )
+                .into()
+            })
+        })
+        .transpose()
+}
+
+/// The `times` handler object
+///
+/// This is the `repeat` helper under another name, for templates written in
+/// the `{{#times 3}}` idiom of other engines. Each registration is configured
+/// on its own:
+///
+/// ```rust
+/// use handlebars_repeat::{RepeatHelper, TimesHelper};
+///
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+/// reg.register_helper("times", Box::new(TimesHelper::default().with_max_count(10)));
+///
+/// let template = "{{#times 3}}{{@index}}{{/times}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "012");
+/// assert!(reg.render_template("{{#times 11}}{{/times}}", &()).is_err());
+/// assert!(reg.render_template("{{#repeat 11}}{{/repeat}}", &()).is_ok());
+/// ```
+pub type TimesHelper = RepeatHelper;
+
+/// The `repeat` handler object
+///
+/// To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("repeat", Box::new(handlebars_repeat::RepeatHelper::default()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RepeatHelper {
+    rounding: Rounding,
+    booleans: bool,
+    lenient: bool,
+    negative: Negative,
+    max_count: Option<u64>,
+    count_key: String,
+    default: Option<u64>,
+    local_prefix: String,
+    local_names: BTreeMap<String, String>,
+    lazy_locals: bool,
+    range: bool,
+}
+
+impl Default for RepeatHelper {
+    fn default() -> Self {
+        Self {
+            rounding: Rounding::default(),
+            booleans: false,
+            lenient: false,
+            negative: Negative::default(),
+            max_count: None,
+            count_key: "count".to_string(),
+            default: None,
+            local_prefix: String::new(),
+            local_names: BTreeMap::new(),
+            lazy_locals: false,
+            range: false,
+        }
+    }
+}
+
+impl RepeatHelper {
+    /// Sets the policy for numeric parameters given as fractional numbers
+    ///
+    /// ```rust
+    /// use handlebars_repeat::{RepeatHelper, Rounding};
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// let helper = RepeatHelper::default().with_rounding(Rounding::Ceil);
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// ```
+    pub fn with_rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Enables coercion of boolean parameters (`true` to 1, `false` to 0)
+    ///
+    /// This allows a feature flag to select between the block and the inverse
+    /// block directly: `{{#repeat enabled}}on{{else}}off{{/repeat}}`.
+    pub fn with_booleans(mut self, booleans: bool) -> Self {
+        self.booleans = booleans;
+        self
+    }
+
+    /// Enables treating a `null` or missing count as zero
+    ///
+    /// Rather than failing the whole render, a lenient helper renders the
+    /// inverse block when the data is incomplete.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Sets the policy for negative counts
+    pub fn with_negative(mut self, negative: Negative) -> Self {
+        self.negative = negative;
+        self
+    }
+
+    /// Sets a maximum number of repetitions for every invocation
+    ///
+    /// Unlike the `max` hash parameter, this limit is not under the control of
+    /// the template author. Larger counts fail with
+    /// `Error::TooManyRepetitions`, even when `clamp=true` is given.
+    ///
+    /// ```rust
+    /// let helper = handlebars_repeat::RepeatHelper::default().with_max_count(10_000);
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// ```
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+
+    /// Sets the key holding the count when given an object (default: `count`)
+    pub fn with_count_key(mut self, key: impl Into<String>) -> Self {
+        self.count_key = key.into();
+        self
+    }
+
+    /// Sets the count used when no count is given
+    ///
+    /// ```rust
+    /// let helper = handlebars_repeat::RepeatHelper::default().with_default(1);
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// assert_eq!(reg.render_template("{{#repeat}}x{{/repeat}}", &()).unwrap(), "x");
+    /// ```
+    pub fn with_default(mut self, count: u64) -> Self {
+        self.default = Some(count);
+        self
+    }
+
+    /// Sets a prefix for the names of the local variables (default: none)
+    ///
+    /// This avoids shadowing the local variables of an enclosing block, such
+    /// as the `@index` of an `each` block. Labels are not prefixed.
+    ///
+    /// ```rust
+    /// let helper = handlebars_repeat::RepeatHelper::default().with_local_prefix("rep_");
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// let template = "{{#each list}}{{#repeat 2}}{{@index}}{{@rep_index}}{{/repeat}}{{/each}}";
+    /// let data = serde_json::json!({"list": [0, 1]});
+    /// assert_eq!(reg.render_template(template, &data).unwrap(), "00011011");
+    /// ```
+    pub fn with_local_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.local_prefix = prefix.into();
+        self
+    }
+
+    /// Renames a local variable, overriding any prefix
+    ///
+    /// ```rust
+    /// let helper = handlebars_repeat::RepeatHelper::default().with_local_name("index", "i");
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// assert_eq!(reg.render_template("{{#repeat 2}}{{@i}}{{/repeat}}", &()).unwrap(), "01");
+    /// ```
+    pub fn with_local_name(mut self, name: impl Into<String>, rename: impl Into<String>) -> Self {
+        self.local_names.insert(name.into(), rename.into());
+        self
+    }
+
+    /// Builds only the local variables which a block names
+    ///
+    /// Building every local variable of every repetition is costly for large
+    /// counts. A lazy helper builds only those named within the block, the
+    /// partials it names or its hash parameters, along with `@index`, `@first`
+    /// and `@last`. A name may appear as a local variable or within a string,
+    /// as in `{{lookup @outer "alpha"}}`, and a block which chooses a partial
+    /// by a subexpression builds them all. A custom helper which reads a local
+    /// variable from the block context must then be given its name, as in
+    /// `{{my-helper "roman"}}`.
+    ///
+    /// ```rust
+    /// let helper = handlebars_repeat::RepeatHelper::default().with_lazy_locals();
+    ///
+    /// let mut reg = handlebars::Handlebars::new();
+    /// reg.register_helper("repeat", Box::new(helper));
+    /// let template = "{{#repeat 3}}{{@roman}} {{/repeat}}";
+    /// assert_eq!(reg.render_template(template, &()).unwrap(), "i ii iii ");
+    /// ```
+    pub fn with_lazy_locals(mut self) -> Self {
+        self.lazy_locals = true;
+        self
+    }
+
+    /// Behaves as the `range` helper: a third parameter is the step and each
+    /// repetition is rendered with its `@index` as the context
+    pub(crate) fn with_range(mut self, range: bool) -> Self {
+        self.range = range;
+        self
+    }
+
+    fn local_name<'a>(&'a self, name: &'a str) -> Cow<'a, str> {
+        match self.local_names.get(name) {
+            Some(rename) => Cow::Borrowed(rename),
+            None if self.local_prefix.is_empty() => Cow::Borrowed(name),
+            None => Cow::Owned(format!("{}{}", self.local_prefix, name)),
+        }
+    }
+
+    fn number(&self, value: &JsonValue) -> Option<u64> {
+        let float = match value {
+            JsonValue::Bool(boolean) if self.booleans => return Some((*boolean).into()),
+
+            JsonValue::String(string) => match string.trim().parse() {
+                Ok(integer) => return Some(integer),
+                Err(..) => string.trim().parse().ok()?,
+            },
+
+            value => match value.as_u64() {
+                Some(integer) => return Some(integer),
+                None => value.as_f64()?,
+            },
+        };
+
+        let float = match self.rounding {
+            Rounding::Reject if float.fract() != 0.0 => return None,
+            Rounding::Reject | Rounding::Truncate => float.trunc(),
+            Rounding::Round => float.round(),
+            Rounding::Ceil => float.ceil(),
+        };
+
+        // The upper bound is exclusive since `u64::MAX as f64` rounds up.
+        if float.is_nan() || float < 0.0 || float >= u64::MAX as f64 {
+            return None;
+        }
+
+        Some(float as u64)
+    }
+
+    /// Converts a count, applying the lenient and negative count policies
+    ///
+    /// Returns `None` if nothing at all should be rendered.
+    fn count(
+        &self,
+        value: &JsonValue,
+        mismatch: impl FnOnce() -> RenderErrorReason,
+    ) -> Result<Option<u64>, RenderError> {
+        if value.is_null() && self.lenient {
+            return Ok(Some(0));
+        }
+
+        if let Some(count) = self.number(value) {
+            return Ok(Some(count));
+        }
+
+        let negative = match value {
+            JsonValue::Number(number) => number.as_f64(),
+            JsonValue::String(string) => string.trim().parse().ok(),
+            _ => None,
+        }
+        .filter(|number| *number < 0.0);
+
+        match (negative, self.negative) {
+            (None, ..) => Err(mismatch().into()),
+            (Some(count), Negative::Error) => Err(Error::NegativeCount(count).into()),
+            (Some(..), Negative::Clamp) => Ok(None),
+            (Some(..), Negative::Inverse) => Ok(Some(0)),
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    fn random(&self, h: &Helper<'_>) -> Result<u64, RenderError> {
+        let min = hash(h, "min", "u64", |v| self.number(v))?.unwrap_or(0);
+        let seed = hash(h, "seed", "u64", |v| self.number(v))?.unwrap_or(0);
+        let max = hash(h, "max", "u64", |v| self.number(v))?
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("repeat", "max".to_string()))?;
+
+        if max < min {
+            return Err(RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                "max".to_string(),
+                "u64 not less than min".to_string(),
+            )
+            .into());
+        }
+
+        Ok(rand::between(seed, min, max))
+    }
+
+    /// Plans the repetitions, or returns `None` when there are none at all
+    fn plan<'a, 'rc>(
+        &self,
+        h: &'a Helper<'rc>,
+        ctx: &Context,
+    ) -> Result<Option<Plan<'a, 'rc>>, RenderError> {
+        let length = hash(h, "len", "bool, \"chars\" or \"bytes\"", |v| match v {
+            JsonValue::Bool(false) => Some(None),
+            JsonValue::Bool(true) => Some(Some(Length::Chars)),
+            JsonValue::String(s) if s == "chars" => Some(Some(Length::Chars)),
+            JsonValue::String(s) if s == "bytes" => Some(Some(Length::Bytes)),
+            _ => None,
+        })?
+        .flatten();
+
+        let keys = hash(h, "keys", "bool", JsonValue::as_bool)?.unwrap_or(false);
+
+        // In the single parameter form, arrays and objects change the scope.
+        let mut scope = None;
+        let first = match (h.param(0), h.param(1)) {
+            #[cfg(feature = "rand")]
+            _ if hash(h, "random", "bool", JsonValue::as_bool)?.unwrap_or(false) => {
+                Some(self.random(h)?)
+            }
+
+            (Some(param), None) if length.is_some() => match (param.value(), length) {
+                (JsonValue::String(string), Some(Length::Chars)) => {
+                    Some(string.chars().count() as u64)
+                }
+
+                (JsonValue::String(string), _) => Some(string.len() as u64),
+
+                _ => {
+                    return Err(RenderErrorReason::ParamTypeMismatchForName(
+                        "repeat",
+                        "0".to_string(),
+                        "string".to_string(),
+                    )
+                    .into())
+                }
+            },
+
+            (Some(param), None) if keys => match param.value() {
+                JsonValue::Object(object) => Some(object.len() as u64),
+                _ => {
+                    return Err(RenderErrorReason::ParamTypeMismatchForName(
+                        "repeat",
+                        "0".to_string(),
+                        "object".to_string(),
+                    )
+                    .into())
+                }
+            },
+
+            (Some(param), None) => match param.value() {
+                JsonValue::Array(items) => {
+                    scope = Some(Scope::Array(param, items));
+                    Some(items.len() as u64)
+                }
+
+                JsonValue::Object(object) => {
+                    let mut object = object.clone();
+                    let count = object.remove(&self.count_key);
+                    scope = Some(Scope::Value(object.into()));
+
+                    match count {
+                        Some(count) => self.count(&count, || {
+                            RenderErrorReason::ParamTypeMismatchForName(
+                                "repeat",
+                                self.count_key.clone(),
+                                "u64".to_string(),
+                            )
+                        })?,
+
+                        None if self.lenient => Some(0),
+                        None => {
+                            return Err(RenderErrorReason::ParamNotFoundForName(
+                                "repeat",
+                                self.count_key.clone(),
+                            )
+                            .into())
+                        }
+                    }
+                }
+
+                value => self.count(value, || {
+                    RenderErrorReason::ParamTypeMismatchForName(
+                        "repeat",
+                        "0".to_string(),
+                        "u64".to_string(),
+                    )
+                })?,
+            },
+
+            (Some(..), Some(..)) => Some(self.param(h, 0)?),
+            (None, _) => {
+                let named = ["count", "times"]
+                    .iter()
+                    .find_map(|name| h.hash_get(name).map(|value| (name, value)));
+
+                match named {
+                    Some((name, value)) => self.count(value.value(), || {
+                        RenderErrorReason::HashTypeMismatchForName(
+                            "repeat",
+                            name.to_string(),
+                            "u64".to_string(),
+                        )
+                    })?,
+
+                    None => match hash(h, "count_path", "string", JsonValue::as_str)? {
+                        Some(path) => match lookup(ctx.data(), path) {
+                            Some(value) => self.count(value, || {
+                                RenderErrorReason::ParamTypeMismatchForName(
+                                    "repeat",
+                                    path.to_string(),
+                                    "u64".to_string(),
+                                )
+                            })?,
+
+                            None if self.lenient => Some(0),
+                            None => {
+                                return Err(
+                                    RenderErrorReason::InvalidJsonPath(path.to_string()).into()
+                                )
+                            }
+                        },
+
+                        None if self.default.is_some() => self.default,
+                        None if self.lenient => Some(0),
+                        None => {
+                            return Err(RenderErrorReason::ParamNotFoundForIndex("repeat", 0).into())
+                        }
+                    },
+                }
+            }
+        };
+
+        // A clamped negative count renders nothing at all.
+        let first = match first {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+
+        let step = match h.param(2) {
+            Some(..) if self.range => self.param(h, 2)?,
+            _ => hash(h, "step", "u64", |v| self.number(v))?.unwrap_or(1),
+        };
+
+        let (start, count) = match h.param(1) {
+            None => match hash(h, "start", "u64", |v| self.number(v))? {
+                Some(start) => (start, first),
+                None => (
+                    hash(h, "base", "u64", |v| self.number(v))?.unwrap_or(0),
+                    first,
+                ),
+            },
+
+            Some(..) => {
+                if step == 0 {
+                    return Err(RenderErrorReason::HashTypeMismatchForName(
+                        "repeat",
+                        "step".to_string(),
+                        "non-zero u64".to_string(),
+                    )
+                    .into());
+                }
+
+                let end = self.param(h, 1)?;
+                let inclusive = hash(h, "inclusive", "bool", JsonValue::as_bool)?;
+                if inclusive.unwrap_or(false) {
+                    match end.checked_sub(first) {
+                        Some(span) => (first, (span / step).saturating_add(1)),
+                        None => (first, 0),
+                    }
+                } else {
+                    let span = end.saturating_sub(first);
+                    (first, span / step + u64::from(span % step != 0))
+                }
+            }
+        };
+
+        let count = match hash(h, "max", "u64", |v| self.number(v))? {
+            Some(max) if count > max => {
+                if !hash(h, "clamp", "bool", JsonValue::as_bool)?.unwrap_or(false) {
+                    return Err(Error::TooManyRepetitions { count, max }.into());
+                }
+
+                max
+            }
+
+            _ => count,
+        };
+
+        if let Some(max) = self.max_count.filter(|max| count > *max) {
+            return Err(Error::TooManyRepetitions { count, max }.into());
+        }
+
+        // Every index fits once the greatest, that of the last repetition, does.
+        if let Some(last) = count.checked_sub(1) {
+            last.checked_mul(step)
+                .and_then(|offset| offset.checked_add(start))
+                .ok_or(Error::IndexOverflow(last))?;
+        }
+
+        Ok(Some(Plan {
+            scope,
+            start,
+            step,
+            count,
+        }))
+    }
+
+    /// The window of repetitions, from `skip` to `end`, which is rendered
+    fn window(&self, h: &Helper<'_>, count: u64) -> Result<(u64, u64), RenderError> {
+        let skip = hash(h, "skip", "u64", |v| self.number(v))?
+            .unwrap_or(0)
+            .min(count);
+        let end = match hash(h, "limit", "u64", |v| self.number(v))? {
+            Some(limit) => skip.saturating_add(limit).min(count),
+            None => count,
+        };
+
+        Ok((skip, end))
+    }
+
+    /// The values of `@index` in the order of rendering, for subexpressions
+    pub(crate) fn indices(&self, h: &Helper<'_>, ctx: &Context) -> Result<JsonValue, RenderError> {
+        let plan = match self.plan(h, ctx)? {
+            Some(plan) => plan,
+            None => return Ok(JsonValue::Array(Vec::new())),
+        };
+
+        let reverse = hash(h, "reverse", "bool", JsonValue::as_bool)?.unwrap_or(false);
+        let (skip, end) = self.window(h, plan.count)?;
+        let indices = (skip..end)
+            .map(|n| if reverse { plan.count - n - 1 } else { n })
+            .map(|i| JsonValue::from(plan.start + i * plan.step))
+            .collect();
+
+        Ok(JsonValue::Array(indices))
+    }
+
+    fn param(&self, h: &Helper<'_>, index: usize) -> Result<u64, RenderError> {
+        let value = h
+            .param(index)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("repeat", index))?
+            .value();
+
+        self.number(value).ok_or_else(|| {
+            RenderErrorReason::ParamTypeMismatchForName(
+                "repeat",
+                index.to_string(),
+                "u64".to_string(),
+            )
+            .into()
+        })
+    }
+}
+
+impl HelperDef for RepeatHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        Ok(ScopedJson::Derived(self.indices(h, ctx)?))
+    }
+
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let escape = hash(h, "escape", "html, none or json", |v| match v.as_str()? {
+            "html" => Some(Escape::Html),
+            "none" => Some(Escape::None),
+            "json" => Some(Escape::Json),
+            _ => None,
+        })?;
+
+        // Any copy of the render context must share the state of the render.
+        shared::Shared::of(h, r, ctx, rc);
+
+        // The escape function belongs to the registry, so the block is instead
+        // rendered from a copy whose expressions escape their own output.
+        match escape {
+            Some(Escape::Html) | Some(Escape::Json) => {
+                let json = escape == Some(Escape::Json);
+                let escaped = escape::Escaped::new(h.template(), h.inverse(), json, r, rc);
+                let mut rc = rc.clone();
+                escaped.install(&mut rc);
+
+                let (template, inverse) = (escaped.template(), escaped.inverse());
+                self.repeat(h, template, inverse, escape, r, ctx, &mut rc, out)
+            }
+
+            _ => self.repeat(h, h.template(), h.inverse(), escape, r, ctx, rc, out),
+        }
+    }
+}
+
+impl RepeatHelper {
+    /// Renders the repetitions of the block, or the inverse block
+    #[allow(clippy::too_many_arguments)]
+    fn repeat<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        template: Option<&'rc Template>,
+        inverse: Option<&'rc Template>,
+        escape: Option<Escape>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let Plan {
+            scope,
+            start,
+            step,
+            count,
+        } = match self.plan(h, ctx)? {
+            Some(plan) => plan,
+            None => return Ok(()),
+        };
+
+        let reverse = hash(h, "reverse", "bool", JsonValue::as_bool)?.unwrap_or(false);
+        let separator = hash(h, "separator", "string", JsonValue::as_str)?;
+        let last_separator = hash(h, "last_separator", "string", JsonValue::as_str)?;
+        let prefix = hash(h, "prefix", "string", JsonValue::as_str)?;
+        let suffix = hash(h, "suffix", "string", JsonValue::as_str)?;
+        let terminator = hash(h, "terminator", "string", JsonValue::as_str)?;
+        let until = match h.hash_get("until") {
+            Some(until) => Some(expression::Expression::new(until).ok_or_else(|| {
+                RenderErrorReason::HashTypeMismatchForName(
+                    "repeat",
+                    "until".to_string(),
+                    "path or expression".to_string(),
+                )
+            })?),
+            None => None,
+        };
+        let this = hash(h, "this", "string", JsonValue::as_str)?;
+        let multiply = hash(h, "multiply", "u64", |v| self.number(v))?;
+
+        // Without an explicit start, a progression begins at one, not zero.
+        let explicit = ["start", "base"]
+            .iter()
+            .any(|name| h.hash_get(name).is_some());
+        let origin = match explicit || h.param(1).is_some() {
+            true => start,
+            false => 1,
+        };
+        let nth = hash(h, "nth", "non-zero u64", |v| {
+            self.number(v).filter(|n| *n > 0)
+        })?;
+        let format = hash(h, "format", "printf-style format", |v| {
+            format::Format::parse(v.as_str()?)
+        })?;
+        #[cfg(feature = "i18n")]
+        let locale = hash(h, "locale", "known locale", |v| {
+            locale::Locale::parse(v.as_str()?)
+        })?
+        .unwrap_or_else(|| locale::Locale::parse("en").unwrap());
+        let sep = hash(h, "sep", "string", JsonValue::as_str)?;
+        let bucket = hash(h, "bucket_size", "non-zero u64", |v| {
+            self.number(v).filter(|n| *n > 0)
+        })?;
+        let group = hash(h, "group_size", "non-zero u64", |v| {
+            self.number(v).filter(|n| *n > 0)
+        })?;
+        let counter = hash(h, "counter", "string", JsonValue::as_str)?;
+        let seed = hash(h, "seed", "u64", |v| self.number(v))?.unwrap_or(0);
+        #[cfg(feature = "uuid")]
+        let uuid_seed = hash(h, "uuid_seed", "u64", |v| self.number(v))?;
+        let ident = hash(h, "ident_prefix", "string", JsonValue::as_str)?;
+        let ident = locals::identifier(ident.unwrap_or(""));
+        let cycle = hash(h, "cycle", "non-empty array or string", |v| {
+            let cycle: Vec<JsonValue> = match v {
+                JsonValue::Array(values) => values.clone(),
+                JsonValue::String(string) if !string.is_empty() => {
+                    string.split(',').map(|s| s.trim().into()).collect()
+                }
+                _ => return None,
+            };
+
+            Some(cycle).filter(|cycle| !cycle.is_empty())
+        })?;
+
+        // Only the window from `skip` to `end` is rendered.
+        let (skip, end) = self.window(h, count)?;
+
+        let wrap = hash(h, "wrap_every", "non-zero u64", |v| {
+            self.number(v).filter(|n| *n > 0)
+        })?;
+        let wrap_open = hash(h, "wrap_open", "string", JsonValue::as_str)?.unwrap_or("");
+        let wrap_close = hash(h, "wrap_close", "string", JsonValue::as_str)?.unwrap_or("");
+
+        let on_error = hash(h, "on_error", "skip, stop or inline", |v| {
+            match v.as_str()? {
+                "skip" => Some(OnError::Skip),
+                "stop" => Some(OnError::Stop),
+                "inline" => Some(OnError::Inline),
+                _ => None,
+            }
+        })?;
+        let placeholder = hash(h, "placeholder", "string", JsonValue::as_str)?;
+
+        let chomp = match hash(h, "chomp", "bool", JsonValue::as_bool)? {
+            Some(chomp) => chomp,
+            None => hash(h, "trim", "bool", JsonValue::as_bool)?.unwrap_or(false),
+        };
+
+        // Count the bytes written beneath any other adapters.
+        let max_bytes = hash(h, "max_bytes", "u64", |v| self.number(v))?;
+        let truncation = hash(h, "truncation", "string", JsonValue::as_str)?;
+        let written = Cell::new(0);
+        let mut counted;
+        let out: &mut dyn Output = match max_bytes {
+            Some(..) => {
+                counted = output::Count::new(out, &written);
+                &mut counted
+            }
+
+            None => out,
+        };
+
+        let indent = hash(h, "indent", "u64 or string", |v| match v {
+            JsonValue::String(string) => Some(Some(string.clone())),
+            v => self
+                .number(v)
+                .map(|n| (n <= MAX_INDENT).then(|| " ".repeat(n as usize))),
+        })?;
+        let indent = match indent {
+            Some(None) => {
+                return Err(RenderErrorReason::HashTypeMismatchForName(
+                    "repeat",
+                    "indent".to_string(),
+                    format!("at most {} spaces", MAX_INDENT),
+                )
+                .into())
+            }
+
+            indent => indent.flatten(),
+        };
+
+        let mut indented;
+        let out: &mut dyn Output = match indent {
+            Some(ref indent) => {
+                indented = output::Indent::new(out, indent);
+                &mut indented
+            }
+
+            None => out,
+        };
+
+        let template = template.ok_or_else(|| RenderErrorReason::BlockContentRequired)?;
+        let sections = sections::Sections::parse(inverse);
+        let template = match sections.single {
+            Some(single) if count == 1 => single,
+            _ => template,
+        };
+
+        // A raw block is concatenated once rather than rendered each time.
+        let literal = match hash(h, "raw", "bool", JsonValue::as_bool)? {
+            Some(true) => {
+                let mut literal = String::new();
+                for element in &template.elements {
+                    match element {
+                        TemplateElement::RawString(string) => literal.push_str(string),
+                        TemplateElement::Comment(..) => (),
+                        _ => return Err(Error::RawExpression.into()),
+                    }
+                }
+
+                if chomp {
+                    literal = literal.trim_matches(|c| c == '\n' || c == '\r').into();
+                }
+
+                Some(literal)
+            }
+
+            _ => None,
+        };
+
+        // Without a hash parameter which observes them, the local variables
+        // of a raw block go unread.
+        let bare = literal.is_some() && until.is_none() && counter.is_none();
+
+        let label = hash(h, "label", "string", JsonValue::as_str)?;
+        let shared = shared::Shared::of(h, r, ctx, rc);
+        let frame = frames::Frame::new(&shared, rc, label);
+        let mut uses = match self.lazy_locals {
+            true => locals::Uses::new(h, &[Some(template), inverse], r, rc),
+            false => locals::Uses::All,
+        };
+        if !self.local_prefix.is_empty() {
+            uses.unprefix(&self.local_prefix);
+        }
+        for (name, rename) in &self.local_names {
+            uses.rename(name, rename);
+        }
+
+        // The enclosing repetitions stay the same throughout the block.
+        let labelled = frame.labelled();
+        let outer = frame.outer();
+        let outer_names = uses.following("outer_");
+        let prefixed = |label: &str| uses.following(&format!("{}_", label));
+        let label_names: Vec<_> = labelled.iter().map(|(label, _)| prefixed(label)).collect();
+        let own_names = label.and_then(prefixed);
+        let flatten_own = own_names.as_ref().map_or(true, |names| !names.is_empty());
+
+        // The local variables are recorded for nested blocks which read them.
+        let recorded = label.is_some()
+            || uses.has("outer")
+            || outer_names.as_ref().map_or(true, |names| !names.is_empty());
+
+        // The position in the sequence of the repetition rendered `n`th
+        let order = |n: u64| if reverse { count - n - 1 } else { n };
+
+        if let Some(template) = sections.before.filter(|_| count > 0) {
+            let mut locals = JsonValue::Null;
+            locals["count"] = count.into();
+            section(template, &frame, locals, r, escape, ctx, rc, out)?;
+        }
+
+        let mut wrapped = false;
+        let mut done = 0;
+        for n in skip..end {
+            if matches!(max_bytes, Some(max) if written.get() >= max) {
+                if let Some(truncation) = truncation {
+                    out.write(truncation)?;
+                }
+
+                break;
+            }
+
+            let i = order(n);
+
+            let index = start + i * step;
+
+            // A bare repetition reads no local variables.
+            let global = shared.global();
+            if bare {
+                shared.counted(global);
+            } else {
+                let mut block = rc.block().cloned().unwrap_or_default();
+                if let Some(name) = h.block_param() {
+                    let mut params = BlockParams::new();
+                    params.add_value(name, index.into())?;
+                    block.set_block_params(params);
+                } else if let Some((name, total)) = h.block_param_pair() {
+                    let mut params = BlockParams::new();
+                    params.add_value(name, index.into())?;
+                    params.add_value(total, count.into())?;
+                    block.set_block_params(params);
+                }
+
+                match scope {
+                    None => (),
+                    Some(Scope::Value(ref value)) => block.set_base_value(value.clone()),
+                    Some(Scope::Array(param, items)) => match param.context_path() {
+                        Some(path) => {
+                            let mut path = path.clone();
+                            path.push(i.to_string());
+                            *block.base_path_mut() = path;
+                        }
+
+                        None => block.set_base_value(items[i as usize].clone()),
+                    },
+                }
+
+                if self.range && this.is_none() {
+                    block.set_base_value(index.into());
+                }
+
+                if let Some(this) = this {
+                    let value = rc.evaluate(ctx, &this.replace("{i}", &index.to_string()))?;
+                    match value.context_path() {
+                        Some(path) => *block.base_path_mut() = path.clone(),
+                        None => block.set_base_value(value.as_json().clone()),
+                    }
+                }
+
+                let position = locals::Position {
+                    n,
+                    i,
+                    index,
+                    count,
+                    skip,
+                    end,
+                };
+                let mut locals = locals::Locals::default();
+                position.set(&mut locals, &uses);
+
+                if let Some(multiply) = multiply {
+                    let value = u32::try_from(i)
+                        .ok()
+                        .and_then(|i| multiply.checked_pow(i))
+                        .and_then(|factor| factor.checked_mul(origin))
+                        .ok_or(Error::ValueOverflow(i))?;
+                    uses.put(&mut locals, "value", || value);
+                }
+
+                if let Some(nth) = nth {
+                    uses.put(&mut locals, "nth", || (n + 1) % nth == 0);
+                }
+
+                uses.put(&mut locals, "ident", || format!("{}_{}", ident, index));
+                uses.put(&mut locals, "depth", || frame.depth());
+                uses.put(&mut locals, "global_index", || global);
+                if let Some(counter) = counter {
+                    let value = shared.next(counter);
+                    uses.put(&mut locals, "counter", || value);
+                }
+
+                uses.put(&mut locals, "hash", || rand::digest(seed, index));
+
+                #[cfg(feature = "rand")]
+                uses.put(&mut locals, "random", || rand::unit(seed, index));
+
+                #[cfg(feature = "uuid")]
+                uses.put(&mut locals, "uuid", || match uuid_seed {
+                    Some(seed) => uuid::seeded(seed, index),
+                    None => uuid::fresh(),
+                });
+
+                uses.put(&mut locals, "prev", || {
+                    n.checked_sub(1).map(|n| start + order(n) * step)
+                });
+                uses.put(&mut locals, "next", || {
+                    let next = Some(n + 1).filter(|n| *n < count);
+                    next.map(|n| start + order(n) * step)
+                });
+
+                if let Some(size) = bucket {
+                    let last = i % size == size - 1 || i == count - 1;
+                    uses.put(&mut locals, "bucket", || i / size);
+                    uses.put(&mut locals, "bucket_first", || i % size == 0);
+                    uses.put(&mut locals, "bucket_last", || last);
+                }
+
+                if let Some(size) = group {
+                    let rendered = n - skip;
+                    let last = rendered % size == size - 1 || n == end - 1;
+                    uses.put(&mut locals, "group_index", || rendered / size);
+                    uses.put(&mut locals, "group_first", || rendered % size == 0);
+                    uses.put(&mut locals, "group_last", || last);
+                }
+
+                if let Some(sep) = sep {
+                    uses.put(&mut locals, "sep", || if n == end - 1 { "" } else { sep });
+                }
+
+                if let Some(ref format) = format {
+                    uses.put(&mut locals, "index_fmt", || format.format(index));
+                }
+
+                #[cfg(feature = "i18n")]
+                uses.put(&mut locals, "index_loc", || locale.format(index));
+
+                if let Some(ref cycle) = cycle {
+                    let value = || cycle[(n % cycle.len() as u64) as usize].clone();
+                    uses.put(&mut locals, "cycle", value);
+                }
+
+                for (label, value) in labelled.iter().filter(|(label, _)| uses.has(label)) {
+                    locals.set(label.clone(), value.clone());
+                }
+
+                if let Some(label) = label.filter(|label| uses.has(label)) {
+                    let own = locals.to_json();
+                    locals.set(label.to_string(), own);
+                }
+
+                // The labelled local variables keep their names, whatever the
+                // names of the others.
+                let mut named = locals::Locals::default();
+                for ((label, value), names) in labelled.iter().zip(&label_names) {
+                    named.flatten(label, value, names.as_ref());
+                }
+
+                if let Some(label) = label.filter(|_| flatten_own) {
+                    named.flatten(label, &locals.to_json(), own_names.as_ref());
+                }
+
+                if let Some(ref outer) = outer {
+                    locals.flatten("outer", outer, outer_names.as_ref());
+                }
+
+                if let Some(outer) = outer.as_ref().filter(|_| uses.has("outer")) {
+                    locals.set("outer", outer.clone());
+                }
+
+                frame.set(
+                    &mut block,
+                    Some(n == end - 1),
+                    recorded.then(|| locals.to_json()),
+                );
+                for (name, value) in locals {
+                    let labels = labelled.iter().map(|(label, _)| label.as_str());
+                    if labels.chain(label).any(|label| label == name) {
+                        block.set_local_var(&name, value);
+                    } else {
+                        block.set_local_var(&self.local_name(&name), value);
+                    }
+                }
+
+                for (name, value) in named {
+                    block.set_local_var(&name, value);
+                }
+
+                block.set_local_var(GUARD, frame.depth().into());
+                rc.push_block(block);
+
+                if let Some(ref until) = until {
+                    if until.holds(r, ctx, rc)? {
+                        rc.pop_block();
+                        break;
+                    }
+                }
+            }
+
+            let mut signal = None;
+
+            // Rendering into a buffer first allows the output of a failed
+            // repetition to be discarded.
+            let body: Option<Cow<'_, str>> = match literal {
+                Some(ref literal) => Some(literal.as_str().into()),
+                None if chomp || on_error.is_some() => {
+                    let mut buffer = StringOutput::new();
+                    let result = attempt(template, r, escape, frame.depth(), ctx, rc, &mut buffer);
+                    let result = companions::catch(result, &mut signal);
+
+                    match (result, on_error) {
+                        (Ok(()), _) => {
+                            let buffer = buffer.into_string()?;
+                            if chomp {
+                                let trimmed = buffer.trim_matches(|c| c == '\n' || c == '\r');
+                                Some(trimmed.to_string().into())
+                            } else {
+                                Some(buffer.into())
+                            }
+                        }
+
+                        (Err(err), None) => return Err(err),
+                        (Err(..), Some(OnError::Skip)) => {
+                            rc.pop_block();
+                            shared.counted(global);
+                            continue;
+                        }
+
+                        (Err(..), Some(OnError::Stop)) => {
+                            rc.pop_block();
+                            break;
+                        }
+
+                        (Err(err), Some(OnError::Inline)) => Some(match placeholder {
+                            Some(placeholder) => placeholder.into(),
+                            None => escaped(&err.reason().to_string(), escape, r, rc).into(),
+                        }),
+                    }
+                }
+
+                None => None,
+            };
+
+            if let Some(prefix) = prefix.filter(|_| done == 0) {
+                out.write(prefix)?;
+            }
+
+            let separator = if n == end - 1 {
+                last_separator.or(separator)
+            } else {
+                separator
+            };
+
+            if let Some(separator) = separator.filter(|_| done > 0) {
+                out.write(separator)?;
+            }
+
+            if matches!(wrap, Some(wrap) if done % wrap == 0) {
+                out.write(wrap_open)?;
+                wrapped = true;
+            }
+
+            match body {
+                Some(body) => out.write(&body)?,
+                None => {
+                    let result = attempt(template, r, escape, frame.depth(), ctx, rc, out);
+                    companions::catch(result, &mut signal)?
+                }
+            }
+
+            if !bare {
+                rc.pop_block();
+                shared.counted(global);
+            }
+
+            if let Some(terminator) = terminator {
+                out.write(terminator)?;
+            }
+
+            done += 1;
+            if matches!(wrap, Some(wrap) if done % wrap == 0) {
+                out.write(wrap_close)?;
+                wrapped = false;
+            }
+
+            if signal == Some(companions::Signal::Break) {
+                break;
+            }
+        }
+
+        // Close the final partial group.
+        if wrapped {
+            out.write(wrap_close)?;
+        }
+
+        if let Some(suffix) = suffix.filter(|_| done > 0) {
+            out.write(suffix)?;
+        }
+
+        if let Some(template) = sections.after.filter(|_| count > 0) {
+            let mut locals = JsonValue::Null;
+            locals["count"] = count.into();
+            locals["rendered"] = done.into();
+            locals["acc"] = frame.accumulated();
+            section(template, &frame, locals, r, escape, ctx, rc, out)?;
+        }
+
+        frame.end();
+
+        if count == 0 {
+            if let Some(template) = sections.inverse {
+                render(template, r, escape, ctx, rc, out)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use serde_json::json;
+
+    const T: &str =
+        "{{#repeat count}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+
+    #[inline]
+    fn render(template: &str, count: u64) -> Result<String, RenderError> {
+        render_with(RepeatHelper::default(), template, json!(count))
+    }
+
+    /// Asserts that a render fails for the expected reason
+    #[track_caller]
+    fn assert_fails(result: Result<String, RenderError>, expected: RenderError) {
+        let err = result.unwrap_err();
+        assert_eq!(
+            format!("{:?}", err.reason()),
+            format!("{:?}", expected.reason())
+        );
+    }
+
+    fn render_with(
+        helper: RepeatHelper,
+        template: &str,
+        count: JsonValue,
+    ) -> Result<String, RenderError> {
+        let data = json!({"name": "foo", "count": count});
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(helper));
+        reg.render_template(template, &data)
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:0:true:true ")]
+    #[case(2, "foo:0:true:false foo:1:false:true ")]
+    #[case(3, "foo:0:true:false foo:1:false:false foo:2:false:true ")]
+    fn success(#[case] count: u64, #[case] output: &str) {
+        assert_eq!(render(T, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "0.0 0.1 ")]
+    #[case(2, "0.0 0.1 1.0 1.1 ")]
+    fn block_param(#[case] count: u64, #[case] output: &str) {
+        let template =
+            "{{#repeat count as |i|}}{{#repeat 2}}{{i}}.{{@index}} {{/repeat}}{{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "0/1 ")]
+    #[case(3, "0/3 1/3 2/3 ")]
+    fn block_param_pair(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count as |i n|}}{{i}}/{{n}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat \"3\"}}{{@index}} {{/repeat}}", "0 1 2 ")]
+    #[case("{{#repeat \" 2 \"}}{{@index}} {{/repeat}}", "0 1 ")]
+    #[case("{{#repeat \"0\"}}{{@index}} {{else}}bar{{/repeat}}", "bar")]
+    #[case("{{#repeat count=\"2\" start=\"5\"}}{{@index}} {{/repeat}}", "5 6 ")]
+    #[case("{{#repeat \"1\" \"4\" step=\"2\"}}{{@index}} {{/repeat}}", "1 3 ")]
+    fn numeric_string(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat \"1.5\"}}{{/repeat}}")]
+    #[case("{{#repeat \"1x\"}}{{/repeat}}")]
+    #[case("{{#repeat \"\"}}{{/repeat}}")]
+    fn wrong_numeric_string(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(Rounding::Reject, json!(3.0), "0 1 2 ")]
+    #[case(Rounding::Truncate, json!(2.5), "0 1 ")]
+    #[case(Rounding::Truncate, json!(0.5), "bar")]
+    #[case(Rounding::Round, json!(2.5), "0 1 2 ")]
+    #[case(Rounding::Round, json!(2.4), "0 1 ")]
+    #[case(Rounding::Ceil, json!(2.1), "0 1 2 ")]
+    #[case(Rounding::Ceil, json!("1.5"), "0 1 ")]
+    #[case(Rounding::Round, json!(-0.4), "bar")]
+    fn float(#[case] rounding: Rounding, #[case] count: JsonValue, #[case] output: &str) {
+        let helper = RepeatHelper::default().with_rounding(rounding);
+        let template = "{{#repeat count}}{{@index}} {{else}}bar{{/repeat}}";
+        assert_eq!(render_with(helper, template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(Rounding::Reject, json!(2.5))]
+    #[case(Rounding::Truncate, json!("1x"))]
+    #[case(Rounding::Round, json!(1e20))]
+    #[case(Rounding::Ceil, json!("NaN"))]
+    fn wrong_float(#[case] rounding: Rounding, #[case] count: JsonValue) {
+        let helper = RepeatHelper::default().with_rounding(rounding);
+        let template = "{{#repeat count}}{{@index}} {{/repeat}}";
+        let err = render_with(helper, template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(json!(true), "foo:0:true:true ")]
+    #[case(json!(false), "bar")]
+    fn boolean(#[case] count: JsonValue, #[case] output: &str) {
+        let helper = RepeatHelper::default().with_booleans(true);
+        assert_eq!(render_with(helper, T, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(json!(true))]
+    #[case(json!(false))]
+    fn wrong_boolean(#[case] count: JsonValue) {
+        let err = render_with(RepeatHelper::default(), T, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(json!([]), "bar")]
+    #[case(json!(["a"]), "0:a:foo:true:true ")]
+    #[case(json!(["a", "b", "c"]), "0:a:foo:true:false 1:b:foo:false:false 2:c:foo:false:true ")]
+    fn array(#[case] count: JsonValue, #[case] output: &str) {
+        let template = "{{#repeat count}}{{@index}}:{{this}}:{{@root.name}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            output
+        );
+    }
+
+    #[rstest]
+    #[case("{{#repeat count reverse=true}}{{this}}{{/repeat}}", "cba")]
+    #[case(
+        "{{#repeat count start=1 separator=\",\"}}{{@index}}={{this}}{{/repeat}}",
+        "1=a,2=b,3=c"
+    )]
+    #[case(
+        "{{#repeat count as |i n|}}{{i}}/{{n}}={{this}} {{/repeat}}",
+        "0/3=a 1/3=b 2/3=c "
+    )]
+    #[case("{{#repeat (lookup this \"count\")}}{{this}}{{/repeat}}", "abc")]
+    fn array_options(#[case] template: &str, #[case] output: &str) {
+        let count = json!(["a", "b", "c"]);
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn array_fields() {
+        let count = json!([{"name": "a"}, {"name": "b"}]);
+        let template = "{{#repeat count}}{{name}}{{/repeat}}";
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            "ab"
+        );
+    }
+
+    #[rstest]
+    #[case(json!({"count": 0, "name": "a"}), "bar")]
+    #[case(json!({"count": 2, "name": "a"}), "a0:true a1:false ")]
+    #[case(json!({"count": "2", "name": "a"}), "a0:true a1:false ")]
+    #[case(json!({"count": 1}), "0:true ")]
+    fn object(#[case] count: JsonValue, #[case] output: &str) {
+        let template = "{{#repeat count}}{{name}}{{@index}}:{{@first}} {{else}}bar{{/repeat}}";
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn object_count_key() {
+        let helper = RepeatHelper::default().with_count_key("replicas");
+        let count = json!({"replicas": 2, "count": 5});
+        let template = "{{#repeat count}}{{@index}}:{{count}}:{{replicas}} {{/repeat}}";
+        assert_eq!(render_with(helper, template, count).unwrap(), "0:5: 1:5: ");
+    }
+
+    #[test]
+    fn object_missing_count() {
+        let template = "{{#repeat count}}{{/repeat}}";
+        let err = render_with(RepeatHelper::default(), template, json!({})).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamNotFoundForName(
+            "repeat",
+            a,
+        ) if a == &"count".to_string())
+        )
+    }
+
+    #[test]
+    fn object_wrong_count_type() {
+        let template = "{{#repeat count}}{{/repeat}}";
+        let count = json!({"count": "foo"});
+        let err = render_with(RepeatHelper::default(), template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"count".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case("{{#repeat count}}foo{{else}}bar{{/repeat}}", json!(null))]
+    #[case("{{#repeat missing}}foo{{else}}bar{{/repeat}}", json!(1))]
+    #[case("{{#repeat}}foo{{else}}bar{{/repeat}}", json!(1))]
+    #[case("{{#repeat start=1}}foo{{else}}bar{{/repeat}}", json!(1))]
+    #[case("{{#repeat count}}foo{{else}}bar{{/repeat}}", json!({}))]
+    #[case("{{#repeat count}}foo{{else}}bar{{/repeat}}", json!({"count": null}))]
+    fn lenient(#[case] template: &str, #[case] count: JsonValue) {
+        let helper = RepeatHelper::default().with_lenient(true);
+        assert_eq!(render_with(helper, template, count).unwrap(), "bar");
+    }
+
+    #[rstest]
+    #[case("{{#repeat count}}foo{{else}}bar{{/repeat}}", json!(null))]
+    #[case("{{#repeat missing}}foo{{else}}bar{{/repeat}}", json!(1))]
+    #[case("{{#repeat count}}foo{{else}}bar{{/repeat}}", json!({"count": null}))]
+    fn strict(#[case] template: &str, #[case] count: JsonValue) {
+        let err = render_with(RepeatHelper::default(), template, count).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::ParamTypeMismatchForName(..)
+        ))
+    }
+
+    #[rstest]
+    #[case(Negative::Clamp, json!(-1), "")]
+    #[case(Negative::Clamp, json!("-3"), "")]
+    #[case(Negative::Inverse, json!(-1), "bar")]
+    #[case(Negative::Inverse, json!(-2.5), "bar")]
+    #[case(Negative::Clamp, json!(1), "foo:0:true:true ")]
+    #[case(Negative::Clamp, json!(0), "bar")]
+    fn negative_policy(#[case] negative: Negative, #[case] count: JsonValue, #[case] output: &str) {
+        let helper = RepeatHelper::default().with_negative(negative);
+        assert_eq!(render_with(helper, T, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(json!(-1), -1.0)]
+    #[case(json!("-2"), -2.0)]
+    #[case(json!({"count": -3}), -3.0)]
+    #[case(json!(-1.5), -1.5)]
+    fn negative_error(#[case] count: JsonValue, #[case] value: f64) {
+        let err = render_with(RepeatHelper::default(), T, count).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e)
+                if e.downcast_ref::<Error>() == Some(&Error::NegativeCount(value))
+        ))
+    }
+
+    #[rstest]
+    #[case("skip=1", 5, "1:true:false 2:false:false 3:false:false 4:false:true ")]
+    #[case("limit=2", 5, "0:true:false 1:false:true ")]
+    #[case("skip=1 limit=2", 5, "1:true:false 2:false:true ")]
+    #[case("skip=4 limit=2", 5, "4:true:true ")]
+    #[case("skip=5 limit=2", 5, "")]
+    #[case("skip=9", 5, "")]
+    #[case("limit=0", 5, "")]
+    #[case("skip=1 limit=2", 0, "bar")]
+    #[case("skip=1 limit=2 reverse=true", 5, "3:true:false 2:false:true ")]
+    #[case("skip=1 limit=2 start=10 step=2", 5, "12:true:false 14:false:true ")]
+    fn window(#[case] hash: &str, #[case] count: u64, #[case] output: &str) {
+        let template = format!(
+            "{{{{#repeat count {}}}}}{{{{@index}}}}:{{{{@first}}}}:{{{{@last}}}} {{{{else}}}}bar{{{{/repeat}}}}",
+            hash
+        );
+        assert_eq!(render(&template, count).unwrap(), output);
+    }
+
+    #[test]
+    fn window_array() {
+        let count = json!(["a", "b", "c", "d"]);
+        let template = "{{#repeat count skip=1 limit=2 separator=\",\"}}{{this}}{{/repeat}}";
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            "b,c"
+        );
+    }
+
+    #[rstest]
+    #[case(json!([{"n": "a"}, {"n": "b", "done": true}, {"n": "c"}]), "done", "a")]
+    #[case(json!([{"n": "a"}, {"n": "b"}, {"n": "c", "done": 1}]), "done", "a,b")]
+    #[case(json!([{"n": "a"}, {"n": "b"}]), "done", "a,b")]
+    #[case(json!([{"n": "a", "done": true}]), "done", "")]
+    #[case(json!([{"n": "a"}, {"n": "b"}, {"n": "c"}]), "@last", "a,b")]
+    #[case(json!([{"n": "a"}, {"n": "b"}, {"n": "c"}]), "@index", "a")]
+    fn until_path(#[case] count: JsonValue, #[case] until: &str, #[case] output: &str) {
+        let template = format!(
+            "{{{{#repeat count until=\"{}\" separator=\",\"}}}}{{{{n}}}}{{{{/repeat}}}}",
+            until
+        );
+        assert_eq!(
+            render_with(RepeatHelper::default(), &template, count).unwrap(),
+            output
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 10 until=\"(eq @index 3)\"}}{{@index}}{{/repeat}}",
+        2,
+        "012"
+    )]
+    #[case(
+        "{{#repeat 10 until=\"gt @index count\"}}{{@index}}{{/repeat}}",
+        2,
+        "012"
+    )]
+    #[case(
+        "{{#repeat 10 until=\"(eq @index count)\" start=1}}{{@index}}{{/repeat}}",
+        4,
+        "123"
+    )]
+    #[case(
+        "{{#repeat 3 until=\"(eq name \\\"foo\\\")\"}}x{{else}}none{{/repeat}}",
+        0,
+        ""
+    )]
+    #[case("{{#repeat 3 until=\"(not @first)\"}}x{{/repeat}}", 0, "x")]
+    fn until_expression(#[case] template: &str, #[case] count: u64, #[case] output: &str) {
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 until=1}}x{{/repeat}}")]
+    #[case("{{#repeat 3 until=(eq @index 3)}}x{{/repeat}}")]
+    #[case("{{#repeat 3 until=\"(eq @index\"}}x{{/repeat}}")]
+    fn wrong_until(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"until".to_string()) && b == &"path or expression".to_string())
+        )
+    }
+
+    #[test]
+    fn until_root() {
+        let template = "{{#repeat 3 until=\"@root.name\"}}x{{/repeat}}";
+        assert_eq!(render(template, 0).unwrap(), "");
+    }
+
+    #[rstest]
+    #[case("nth=3", 7, "0:false 1:false 2:true 3:false 4:false 5:true 6:false ")]
+    #[case("nth=1", 2, "0:true 1:true ")]
+    #[case("nth=2 start=5", 3, "5:false 6:true 7:false ")]
+    #[case("nth=2 skip=1 limit=2", 5, "1:true 2:false ")]
+    #[case("", 2, "0: 1: ")]
+    fn nth(#[case] hash: &str, #[case] count: u64, #[case] output: &str) {
+        let template = format!(
+            "{{{{#repeat count {}}}}}{{{{@index}}}}:{{{{@nth}}}} {{{{/repeat}}}}",
+            hash
+        );
+        assert_eq!(render(&template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 nth=0}}{{/repeat}}")]
+    #[case("{{#repeat 3 nth=\"foo\"}}{{/repeat}}")]
+    fn wrong_nth(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"nth".to_string()) && b == &"non-zero u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case("cycle=\"odd,even\"", 3, "0:odd 1:even 2:odd ")]
+    #[case("cycle=\"a, b ,c\"", 4, "0:a 1:b 2:c 3:a ")]
+    #[case("cycle=\"x\"", 2, "0:x 1:x ")]
+    #[case("cycle=@root.classes", 3, "0:1 1:two 2:1 ")]
+    #[case("cycle=\"a,b\" reverse=true", 3, "2:a 1:b 0:a ")]
+    #[case("", 2, "0: 1: ")]
+    fn cycle(#[case] hash: &str, #[case] count: u64, #[case] output: &str) {
+        let template = format!(
+            "{{{{#repeat count {}}}}}{{{{@index}}}}:{{{{@cycle}}}} {{{{/repeat}}}}",
+            hash
+        );
+        let data = json!({"count": count, "classes": [1, "two"]});
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(&template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 cycle=\"\"}}{{/repeat}}", json!([]))]
+    #[case("{{#repeat 3 cycle=count}}{{/repeat}}", json!([]))]
+    #[case("{{#repeat 3 cycle=count}}{{/repeat}}", json!(1))]
+    fn wrong_cycle(#[case] template: &str, #[case] count: JsonValue) {
+        let err = render_with(RepeatHelper::default(), template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"cycle".to_string()) && b == &"non-empty array or string".to_string())
+        )
+    }
+
+    #[cfg(feature = "rand")]
+    #[rstest]
+    #[case("min=2 max=8 seed=42", 2, 8)]
+    #[case("max=8 seed=1", 0, 8)]
+    #[case("min=3 max=3", 3, 3)]
+    #[case("min=0 max=0", 0, 0)]
+    fn random(#[case] hash: &str, #[case] min: usize, #[case] max: usize) {
+        let template = format!("{{{{#repeat random=true {}}}}}x{{{{/repeat}}}}", hash);
+        let output = render(&template, 0).unwrap();
+        assert_eq!(render(&template, 0).unwrap(), output);
+        assert!(min <= output.len() && output.len() <= max);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_seeds() {
+        let outputs: std::collections::BTreeSet<_> = (0..32)
+            .map(|seed| {
+                let template = format!(
+                    "{{{{#repeat random=true max=8 seed={}}}}}x{{{{/repeat}}}}",
+                    seed
+                );
+                render(&template, 0).unwrap()
+            })
+            .collect();
+
+        assert!(outputs.len() > 1);
+    }
+
+    #[cfg(feature = "rand")]
+    #[rstest]
+    #[case("{{#repeat random=true min=2}}x{{/repeat}}")]
+    #[case("{{#repeat random=true min=4 max=3}}x{{/repeat}}")]
+    fn wrong_random(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(
+            matches!(
+                err.reason(),
+                RenderErrorReason::ParamNotFoundForName("repeat", a) if a == "max"
+            ) || matches!(
+                err.reason(),
+                RenderErrorReason::HashTypeMismatchForName("repeat", a, ..) if a == "max"
+            )
+        )
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(2, "0:true:false 1:false:true ")]
+    #[case(3, "0:true:false 1:false:false 2:false:true ")]
+    fn max(#[case] count: u64, #[case] output: &str) {
+        let template =
+            "{{#repeat count max=3}}{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(4, "0:true:false 1:false:false 2:false:true ")]
+    #[case(100, "0:true:false 1:false:false 2:false:true ")]
+    fn max_clamp(#[case] count: u64, #[case] output: &str) {
+        let template =
+            "{{#repeat count max=3 clamp=true}}{{@index}}:{{@first}}:{{@last}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat count max=3}}{{/repeat}}", 4)]
+    #[case("{{#repeat count max=3 clamp=false}}{{/repeat}}", 100)]
+    #[case("{{#repeat 0 count max=3}}{{/repeat}}", 5)]
+    fn max_error(#[case] template: &str, #[case] count: u64) {
+        let err = render(template, count).unwrap_err();
+        let expected = Error::TooManyRepetitions { count, max: 3 };
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e) if e.downcast_ref::<Error>() == Some(&expected)
+        ))
+    }
+
+    #[rstest]
+    #[case("{{#repeat count}}{{/repeat}}", 3, true)]
+    #[case("{{#repeat count}}{{/repeat}}", 4, false)]
+    #[case("{{#repeat count max=10 clamp=true}}{{/repeat}}", 5, false)]
+    #[case("{{#repeat count max=2 clamp=true}}{{/repeat}}", 5, true)]
+    #[case("{{#repeat 2 count}}{{/repeat}}", 5, true)]
+    fn max_count(#[case] template: &str, #[case] count: u64, #[case] ok: bool) {
+        let helper = RepeatHelper::default().with_max_count(3);
+        match render_with(helper, template, json!(count)) {
+            Ok(..) => assert!(ok),
+            Err(err) => {
+                let expected = Error::TooManyRepetitions { count, max: 3 };
+                assert!(!ok);
+                assert!(matches!(
+                    err.reason(),
+                    RenderErrorReason::NestedError(e) if e.downcast_ref::<Error>() == Some(&expected)
+                ))
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:10:true:true ")]
+    #[case(3, "foo:10:true:false foo:11:false:false foo:12:false:true ")]
+    fn start(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count start=10}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "")]
+    #[case(1, "1 ")]
+    #[case(3, "1 2 3 ")]
+    fn base(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count base=1}}{{@index}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "")]
+    #[case(3, "5 6 7 ")]
+    fn start_overrides_base(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count base=1 start=5}}{{@index}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[test]
+    fn start_at_maximum() {
+        let template = "{{#repeat 1 start=18446744073709551615}}{{@index}}:{{@index1}}:{{@ordinal}}{{/repeat}}";
+        assert_eq!(
+            render(template, 0).unwrap(),
+            "18446744073709551615::18446744073709551615th"
+        );
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2 start=18446744073709551615}}{{@index}}{{/repeat}}", 1)]
+    #[case("{{#repeat 3 start=18446744073709551614}}{{@index}}{{/repeat}}", 2)]
+    fn start_overflow(#[case] template: &str, #[case] last: u64) {
+        let err = render(template, 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e)
+                if e.downcast_ref::<Error>() == Some(&Error::IndexOverflow(last))
+        ));
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:0:true:true ")]
+    #[case(3, "foo:0:true:false foo:2:false:false foo:4:false:true ")]
+    fn step(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count step=2}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 step=18446744073709551615}}{{@index}}{{/repeat}}", 2)]
+    #[case(
+        "{{#repeat 2 start=1 step=18446744073709551615}}{{@index}}{{/repeat}}",
+        1
+    )]
+    #[case(
+        "{{#repeat 3 step=18446744073709551615 reverse=true}}{{@index}}{{/repeat}}",
+        2
+    )]
+    fn step_overflow(#[case] template: &str, #[case] last: u64) {
+        let err = render(template, 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e)
+                if e.downcast_ref::<Error>() == Some(&Error::IndexOverflow(last))
+        ));
+    }
+
+    #[rstest]
+    #[case(0, "")]
+    #[case(3, "5 8 11 ")]
+    fn start_step(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count start=5 step=3}}{{@index}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 2 5}}{{@index}}:{{@first}}:{{@last}} {{/repeat}}",
+        "2:true:false 3:false:false 4:false:true "
+    )]
+    #[case(
+        "{{#repeat 2 3}}{{@index}}:{{@first}}:{{@last}} {{/repeat}}",
+        "2:true:true "
+    )]
+    #[case("{{#repeat 5 5}}{{@index}} {{else}}bar{{/repeat}}", "bar")]
+    #[case("{{#repeat 5 2}}{{@index}} {{else}}bar{{/repeat}}", "bar")]
+    #[case("{{#repeat 1 8 step=3}}{{@index}} {{/repeat}}", "1 4 7 ")]
+    #[case("{{#repeat 1 7 step=3}}{{@index}} {{/repeat}}", "1 4 ")]
+    #[case("{{#repeat 0 3 reverse=true}}{{@index}} {{/repeat}}", "2 1 0 ")]
+    #[case("{{#repeat 0 3 start=5}}{{@index}} {{/repeat}}", "0 1 2 ")]
+    #[case("{{#repeat 3 5 as |i n|}}{{i}}/{{n}} {{/repeat}}", "3/2 4/2 ")]
+    fn range(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn range_zero_step() {
+        let err = render("{{#repeat 0 3 step=0}}{{/repeat}}", 0).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"step".to_string()) && b == &"non-zero u64".to_string())
+        )
+    }
+
+    #[test]
+    fn wrong_range_type() {
+        let err = render("{{#repeat 0 \"foo\"}}{{/repeat}}", 0).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"1".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:0:true:true ")]
+    #[case(3, "foo:2:true:false foo:1:false:false foo:0:false:true ")]
+    fn reverse(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count reverse=true}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "")]
+    #[case(3, "7 4 1 ")]
+    fn reverse_start_step(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count start=1 step=3 reverse=true}}{{@index}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "0")]
+    #[case(3, "0, 1, 2")]
+    fn separator(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count separator=\", \"}}{{@index}}{{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn wrong_start_type(#[case] count: u64) {
+        let template = "{{#repeat count start=\"foo\"}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"start".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn wrong_separator_type(#[case] count: u64) {
+        let template = "{{#repeat count separator=1}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"separator".to_string()) && b == &"string".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:0:true:true ")]
+    #[case(2, "foo:0:true:false foo:1:false:true ")]
+    fn times(#[case] count: u64, #[case] output: &str) {
+        let template =
+            "{{#repeat times=count}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "foo:0:true:true ")]
+    #[case(2, "foo:0:true:false foo:1:false:true ")]
+    fn count_hash(#[case] count: u64, #[case] output: &str) {
+        let template =
+            "{{#repeat count=count}}{{name}}:{{@index}}:{{@first}}:{{@last}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn missing_count(#[case] count: u64) {
+        let template = "{{#repeat start=1}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::ParamNotFoundForIndex("repeat", 0)
+        ))
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat count_path=\"config.replicas\"}}{{@index}}{{/repeat}}",
+        "012"
+    )]
+    #[case("{{#repeat count_path=\"config.sizes.1\"}}{{@index}}{{/repeat}}", "01")]
+    #[case(
+        "{{#with config}}{{#repeat count_path=\"config.replicas\"}}{{@index}}{{/repeat}}{{/with}}",
+        "012"
+    )]
+    #[case(
+        "{{#repeat count_path=\"config.replicas\" start=1}}{{@index}}{{/repeat}}",
+        "123"
+    )]
+    #[case(
+        "{{#repeat 1 count_path=\"config.replicas\"}}{{@index}}{{/repeat}}",
+        "0"
+    )]
+    fn count_path(#[case] template: &str, #[case] output: &str) {
+        let data = json!({"config": {"replicas": 3, "sizes": [1, "2"]}});
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("config.missing")]
+    #[case("config.sizes.2")]
+    #[case("config.sizes.x")]
+    #[case("config.replicas.x")]
+    fn missing_count_path(#[case] path: &str) {
+        let template = format!("{{{{#repeat count_path=\"{}\"}}}}{{{{/repeat}}}}", path);
+        let data = json!({"config": {"replicas": 3, "sizes": [1, "2"]}});
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let err = reg.render_template(&template, &data).unwrap_err();
+        assert!(matches!(err.reason(), RenderErrorReason::InvalidJsonPath(p) if p == path));
+
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "repeat",
+            Box::new(RepeatHelper::default().with_lenient(true)),
+        );
+        assert_eq!(reg.render_template(&template, &data).unwrap(), "");
+    }
+
+    #[test]
+    fn wrong_count_path_type() {
+        let template = "{{#repeat count_path=\"config\"}}{{/repeat}}";
+        let data = json!({"config": {"replicas": 3}});
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let err = reg.render_template(template, &data).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"config".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn wrong_times_type(#[case] count: u64) {
+        let template = "{{#repeat times=\"foo\"}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::HashTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"times".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn missing_arg(#[case] count: u64) {
+        let template = "{{#repeat}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::ParamNotFoundForIndex("repeat", 0)
+        ))
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn wrong_arg_type(#[case] count: u64) {
+        let template = "{{#repeat \"foo\"}}{{name}}{{/repeat}}";
+        let err = render(template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"u64".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    fn missing_block(#[case] count: u64) {
+        let template = "{{repeat count}}";
+        let err = render(template, count).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::BlockContentRequired
+        ))
+    }
+
+    #[rstest]
+    #[case(json!("Repeat"), "len=true", "======")]
+    #[case(json!("Repeat"), "len=\"chars\"", "======")]
+    #[case(json!("Grüße"), "len=true", "=====")]
+    #[case(json!("Grüße"), "len=\"bytes\"", "=======")]
+    #[case(json!("3"), "len=true", "=")]
+    #[case(json!("3"), "len=false", "===")]
+    #[case(json!(""), "len=true", "bar")]
+    fn length(#[case] count: JsonValue, #[case] hash: &str, #[case] output: &str) {
+        let template = format!(
+            "{{{{#repeat count {}}}}}={{{{else}}}}bar{{{{/repeat}}}}",
+            hash
+        );
+        assert_eq!(
+            render_with(RepeatHelper::default(), &template, count).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn wrong_length_param() {
+        let template = "{{#repeat count len=true}}={{/repeat}}";
+        let err = render(template, 3).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"string".to_string())
+        )
+    }
+
+    #[test]
+    fn wrong_length_hash() {
+        let template = "{{#repeat count len=\"words\"}}={{/repeat}}";
+        let err = render(template, 3).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName("repeat", a, ..) if a == "len"
+        ))
+    }
+
+    #[rstest]
+    #[case(json!({}), "bar")]
+    #[case(json!({"a": 1}), "0:foo ")]
+    #[case(json!({"a": 1, "count": 7, "c": [3]}), "0:foo 1:foo 2:foo ")]
+    fn keys(#[case] count: JsonValue, #[case] output: &str) {
+        let template = "{{#repeat count keys=true}}{{@index}}:{{name}} {{else}}bar{{/repeat}}";
+        assert_eq!(
+            render_with(RepeatHelper::default(), template, count).unwrap(),
+            output
+        );
+    }
+
+    #[rstest]
+    #[case(json!(3))]
+    #[case(json!([1, 2]))]
+    fn wrong_keys(#[case] count: JsonValue) {
+        let template = "{{#repeat count keys=true}}{{/repeat}}";
+        let err = render_with(RepeatHelper::default(), template, count).unwrap_err();
+        assert!(
+            matches!(err.reason(), RenderErrorReason::ParamTypeMismatchForName(
+            "repeat",
+            a,
+            b,
+        ) if (a == &"0".to_string()) && b == &"object".to_string())
+        )
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 this=\"a.[{i}]\" as |i|}}{{this}}{{lookup @root.b i}} {{/repeat}}",
+        "x1 y2 z3 "
+    )]
+    #[case("{{#repeat 2 this=\"a.{i}\" start=1}}{{this}}{{/repeat}}", "yz")]
+    #[case("{{#repeat 2 this=\"c.[{i}]\"}}{{n}}{{/repeat}}", "pq")]
+    #[case(
+        "{{#with c}}{{#repeat 2 this=\"[{i}]\"}}{{n}}{{/repeat}}{{/with}}",
+        "pq"
+    )]
+    #[case("{{#repeat 2 this=\"missing.[{i}]\"}}[{{this}}]{{/repeat}}", "[][]")]
+    #[case("{{#repeat 2 this=\"a.[{i}]\" reverse=true}}{{this}}{{/repeat}}", "yx")]
+    fn this(#[case] template: &str, #[case] output: &str) {
+        let data = json!({
+            "a": ["x", "y", "z"],
+            "b": ["1", "2", "3"],
+            "c": [{"n": "p"}, {"n": "q"}],
+        });
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2 indent=2}}x{{@index}}\n{{/repeat}}", "  x0\n  x1\n")]
+    #[case(
+        "{{#repeat 2 indent=\"\\t\"}}x\ny\n{{/repeat}}",
+        "\tx\n\ty\n\tx\n\ty\n"
+    )]
+    #[case("{{#repeat 2 indent=\"4\"}}x\n\n{{/repeat}}", "4x\n\n4x\n\n")]
+    #[case("{{#repeat 2 indent=1 separator=\",\"}}x{{/repeat}}", " x,x")]
+    #[case("{{#repeat 0 indent=1}}x{{else}}a\nb{{/repeat}}", " a\n b")]
+    #[case("{{#repeat 2 indent=0}}x\n{{/repeat}}", "x\nx\n")]
+    fn indent(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn indent_partial() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_partial("item", "a: {{@index}}\nb: {{@index}}\n")
+            .unwrap();
+
+        let template = "{{#repeat 2 indent=2}}\n{{> item}}{{/repeat}}";
+        let output = reg.render_template(template, &()).unwrap();
+        assert_eq!(output, "  a: 0\n  b: 0\n  a: 1\n  b: 1\n");
+    }
+
+    #[test]
+    fn indent_too_wide() {
+        assert_eq!(
+            render("{{#repeat 1 indent=1024}}x{{/repeat}}", 0).unwrap(),
+            format!("{}x", " ".repeat(1024))
+        );
+
+        let err = render("{{#repeat 1 indent=1025}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"indent".to_string()) && b == &"at most 1024 spaces".to_string()));
+    }
+
+    #[test]
+    fn wrong_indent_type() {
+        let err = render("{{#repeat 2 indent=true}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"indent".to_string()) && b == &"u64 or string".to_string()));
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 chomp=true separator=\",\"}}\n{{@index}}\n{{/repeat}}",
+        "0,1,2"
+    )]
+    #[case("{{#repeat 2 trim=true}}\r\n\n x \n\r\n{{/repeat}}", " x  x ")]
+    #[case("{{#repeat 2 chomp=false}}\nx\n{{/repeat}}", "x\nx\n")]
+    #[case("{{#repeat 2 chomp=false trim=true}}\nx\n{{/repeat}}", "x\nx\n")]
+    #[case(
+        "{{#repeat 2 chomp=true indent=2}}\na\nb\n{{/repeat}}",
+        "  a\n  ba\n  b"
+    )]
+    fn chomp(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "[0]")]
+    #[case(2, "[01]")]
+    #[case(3, "[01][2]")]
+    #[case(4, "[01][23]")]
+    fn wrap_every(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count wrap_every=2 wrap_open=\"[\" wrap_close=\"]\"}}{{@index}}{{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 wrap_every=2 wrap_open=\"(\" wrap_close=\")\" separator=\",\"}}{{@index}}{{/repeat}}", "(0,1),(2)")]
+    #[case("{{#repeat 5 wrap_every=2 wrap_open=\"(\" wrap_close=\")\" skip=1 limit=3}}{{@index}}{{/repeat}}", "(12)(3)")]
+    #[case("{{#repeat 5 wrap_every=2 wrap_open=\"(\" wrap_close=\")\" until=\"@last\"}}{{@index}}{{/repeat}}", "(01)(23)")]
+    #[case("{{#repeat 5 wrap_every=2 wrap_open=\"(\" wrap_close=\")\" until=\"@nth\" nth=3}}{{@index}}{{/repeat}}", "(01)")]
+    #[case(
+        "{{#repeat 3 wrap_every=2 wrap_close=\"\\n\"}}{{@index}}{{/repeat}}",
+        "01\n2\n"
+    )]
+    #[case("{{#repeat 3 wrap_open=\"(\"}}{{@index}}{{/repeat}}", "012")]
+    fn wrap(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_wrap_every_type() {
+        let err = render("{{#repeat 3 wrap_every=0}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"wrap_every".to_string()) && b == &"non-zero u64".to_string()));
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "(0;)")]
+    #[case(3, "(0;,1;,2;)")]
+    fn framing(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count prefix=\"(\" suffix=\")\" terminator=\";\" separator=\",\"}}{{@index}}{{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 prefix=\"<\" suffix=\">\" until=\"@first\"}}x{{/repeat}}",
+        ""
+    )]
+    #[case(
+        "{{#repeat 5 prefix=\"<\" suffix=\">\" skip=2 limit=2}}{{@index}}{{/repeat}}",
+        "<23>"
+    )]
+    #[case("{{#repeat 3 prefix=\"<\" suffix=\">\" wrap_every=2 wrap_open=\"(\" wrap_close=\")\"}}{{@index}}{{/repeat}}", "<(01)(2)>")]
+    #[case("{{#repeat 3 terminator=\";\" wrap_every=2 wrap_open=\"(\" wrap_close=\")\"}}{{@index}}{{/repeat}}", "(0;1;)(2;)")]
+    #[case(
+        "{{#repeat 2 terminator=\"\\n\" indent=2}}{{@index}}{{/repeat}}",
+        "  0\n  1\n"
+    )]
+    fn framing_with(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(1, "0")]
+    #[case(2, "0 and 1")]
+    #[case(3, "0, 1 and 2")]
+    #[case(4, "0, 1, 2 and 3")]
+    fn last_separator(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count separator=\", \" last_separator=\" and \"}}{{@index}}{{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 last_separator=\"&\"}}{{@index}}{{/repeat}}", "01&2")]
+    #[case(
+        "{{#repeat 5 separator=\",\" last_separator=\"&\" skip=1 limit=3}}{{@index}}{{/repeat}}",
+        "1,2&3"
+    )]
+    #[case(
+        "{{#repeat 3 separator=\",\" last_separator=\"&\" reverse=true}}{{@index}}{{/repeat}}",
+        "2,1&0"
+    )]
+    fn last_separator_with(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 1 5 inclusive=true}}{{@index}}{{/repeat}}", "12345")]
+    #[case("{{#repeat 1 5 inclusive=false}}{{@index}}{{/repeat}}", "1234")]
+    #[case("{{#repeat 3 3 inclusive=true}}{{@index}}{{/repeat}}", "3")]
+    #[case(
+        "{{#repeat 4 3 inclusive=true}}{{@index}}{{else}}bar{{/repeat}}",
+        "bar"
+    )]
+    #[case("{{#repeat 0 6 step=3 inclusive=true}}{{@index}}{{/repeat}}", "036")]
+    #[case("{{#repeat 0 7 step=3 inclusive=true}}{{@index}}{{/repeat}}", "036")]
+    #[case(
+        "{{#repeat 1 3 inclusive=true reverse=true}}{{@index}}{{/repeat}}",
+        "321"
+    )]
+    #[case("{{#repeat 1 3 inclusive=true as |i n|}}{{n}}{{/repeat}}", "333")]
+    fn inclusive(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 6 start=1 multiply=2}}{{@index}}={{@value}} {{/repeat}}",
+        "1=1 2=2 3=4 4=8 5=16 6=32 "
+    )]
+    #[case(
+        "{{#repeat 4 multiply=2}}{{@index}}={{@value}} {{/repeat}}",
+        "0=1 1=2 2=4 3=8 "
+    )]
+    #[case("{{#repeat 3 start=0 multiply=2}}{{@value}} {{/repeat}}", "0 0 0 ")]
+    #[case("{{#repeat 3 base=5 multiply=2}}{{@value}} {{/repeat}}", "5 10 20 ")]
+    #[case(
+        "{{#repeat 3 start=3 multiply=10 reverse=true}}{{@value}} {{/repeat}}",
+        "300 30 3 "
+    )]
+    #[case("{{#repeat 2 5 multiply=3}}{{@value}} {{/repeat}}", "2 6 18 ")]
+    #[case("{{#repeat 3 start=7 multiply=1}}{{@value}} {{/repeat}}", "7 7 7 ")]
+    #[case("{{#repeat 3 start=1 multiply=0}}{{@value}} {{/repeat}}", "1 0 0 ")]
+    #[case("{{#repeat 2 start=1}}[{{@value}}]{{/repeat}}", "[][]")]
+    fn multiply(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn multiply_overflow() {
+        let template = "{{#repeat 100 start=1 multiply=2}}{{@value}}{{/repeat}}";
+        let err = render(template, 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e)
+                if e.downcast_ref::<Error>() == Some(&Error::ValueOverflow(64))
+        ));
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 1000 max_bytes=10 truncation=\"...\"}}{{@index}} {{/repeat}}",
+        "0 1 2 3 4 ..."
+    )]
+    #[case("{{#repeat 1000 max_bytes=10}}{{@index}} {{/repeat}}", "0 1 2 3 4 ")]
+    #[case(
+        "{{#repeat 5 max_bytes=10 truncation=\"...\"}}{{@index}} {{/repeat}}",
+        "0 1 2 3 4 "
+    )]
+    #[case(
+        "{{#repeat 9 max_bytes=3 truncation=\"...\"}}abcd{{/repeat}}",
+        "abcd..."
+    )]
+    #[case("{{#repeat 9 max_bytes=0 truncation=\"...\"}}abcd{{/repeat}}", "...")]
+    #[case(
+        "{{#repeat 9 max_bytes=6 separator=\",\" indent=2}}{{@index}}\n{{/repeat}}",
+        "  0\n  ,1\n"
+    )]
+    #[case("{{#repeat 9 max_bytes=4 prefix=\"[\" suffix=\"]\" truncation=\"..\"}}{{@index}}{{/repeat}}", "[012..]")]
+    fn max_bytes(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(false, "{{#repeat 1}}{{v}}{{/repeat}}", "&lt;&quot;a&quot;&gt;")]
+    #[case(
+        false,
+        "{{#repeat 1 escape=\"none\"}}{{v}}{{/repeat}}{{v}}",
+        "<\"a\">&lt;&quot;a&quot;&gt;"
+    )]
+    #[case(
+        false,
+        "{{#repeat 1 escape=\"json\"}}{{v}}{{/repeat}}{{v}}",
+        "<\\\"a\\\">&lt;&quot;a&quot;&gt;"
+    )]
+    #[case(
+        false,
+        "{{#repeat 0 escape=\"none\"}}x{{else}}{{v}}{{/repeat}}",
+        "<\"a\">"
+    )]
+    #[case(
+        false,
+        "{{#repeat 2 escape=\"json\" chomp=true}}\n{{v}}\n{{/repeat}}",
+        "<\\\"a\\\"><\\\"a\\\">"
+    )]
+    #[case(true, "{{#repeat 1}}{{v}}{{/repeat}}", "<\"a\">")]
+    #[case(
+        true,
+        "{{#repeat 1 escape=\"html\"}}{{v}}{{/repeat}}{{v}}",
+        "&lt;&quot;a&quot;&gt;<\"a\">"
+    )]
+    #[case(true, "{{#repeat 1 escape=\"html\"}}{{{v}}}{{/repeat}}", "<\"a\">")]
+    #[case(true, "{{#repeat 1 escape=\"html\" as |i|}}{{#repeat 1 escape=\"none\"}}{{v}}{{i}}{{/repeat}}{{v}}{{/repeat}}", "<\"a\">0&lt;&quot;a&quot;&gt;")]
+    fn escape(#[case] raw: bool, #[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        if raw {
+            reg.register_escape_fn(no_escape);
+        }
+
+        let data = json!({"v": "<\"a\">"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 2 escape=\"json\"}}{{> item}}{{/repeat}}",
+        "<\\\"a\\\"><\\\"a\\\">"
+    )]
+    #[case(
+        "{{#repeat 1 escape=\"json\"}}{{> outer}}{{/repeat}}{{> item}}",
+        "[<\\\"a\\\">]&lt;&quot;a&quot;&gt;"
+    )]
+    #[case(
+        "{{#*inline \"item\"}}({{v}}){{/inline}}{{#repeat 1 escape=\"json\"}}{{> item}}{{/repeat}}",
+        "(<\\\"a\\\">)"
+    )]
+    #[case("{{#repeat 1 escape=\"html\"}}{{#repeat 1 escape=\"json\"}}{{v}}{{/repeat}}{{v}}{{/repeat}}", "<\\\"a\\\">&lt;&quot;a&quot;&gt;")]
+    #[case(
+        "{{#repeat 1 escape=\"json\"}}{{lookup this \"v\"}}{{/repeat}}",
+        "<\\\"a\\\">"
+    )]
+    #[case("{{#repeat 2 escape=\"json\"}}{{counter \"c\"}}{{/repeat}}", "12")]
+    fn escape_within(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("counter", Box::new(CounterHelper));
+        reg.register_partial("item", "{{v}}").unwrap();
+        reg.register_partial("outer", "[{{> item}}]").unwrap();
+
+        let data = json!({"v": "<\"a\">"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_escape() {
+        let err = render("{{#repeat 1 escape=\"xml\"}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"escape".to_string()) && b == &"html, none or json".to_string()));
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 raw=true}}=-{{/repeat}}", "=-=-=-")]
+    #[case("{{#repeat 3 raw=true}}a{{! note }}b{{/repeat}}", "ababab")]
+    #[case("{{#repeat 2 raw=true separator=\",\"}}x{{/repeat}}", "x,x")]
+    #[case("{{#repeat 2 raw=true chomp=true}}\n\nx\n{{/repeat}}", "xx")]
+    #[case("{{#repeat 0 raw=true}}x{{else}}{{name}}{{/repeat}}", "foo")]
+    #[case("{{#repeat 2 raw=false}}{{name}}{{/repeat}}", "foofoo")]
+    #[case("{{#repeat 3 raw=true until=\"@last\"}}x{{/repeat}}", "xx")]
+    #[case(
+        "{{#repeat 2 raw=true}}x{{/repeat}}{{#repeat 2 raw=true}}{{/repeat}}",
+        "xx"
+    )]
+    fn raw(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3 raw=true}}{{name}}{{/repeat}}")]
+    #[case("{{#repeat 3 raw=true}}{{{name}}}{{/repeat}}")]
+    #[case("{{#repeat 3 raw=true}}{{#if name}}x{{/if}}{{/repeat}}")]
+    #[case("{{#repeat 0 raw=true}}{{name}}{{/repeat}}")]
+    fn raw_expression(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::NestedError(e)
+                if e.downcast_ref::<Error>() == Some(&Error::RawExpression)
+        ));
+    }
+
+    /// Writes the local variable named by the hash parameter, read from the
+    /// block context
+    struct Peek;
+
+    impl HelperDef for Peek {
+        fn call<'reg: 'rc, 'rc>(
+            &self,
+            h: &Helper<'rc>,
+            _: &'reg Handlebars<'reg>,
+            _: &'rc Context,
+            rc: &mut RenderContext<'reg, 'rc>,
+            out: &mut dyn Output,
+        ) -> HelperResult {
+            let name = h.hash_get("local").and_then(|v| v.value().as_str());
+            let value = rc
+                .block()
+                .and_then(|b| b.get_local_var(name.unwrap_or_default()));
+            Ok(out.write(&value.map(JsonValue::to_string).unwrap_or_default())?)
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 2}}{{peek local=\"odd\"}} {{/repeat}}",
+        false,
+        "false true "
+    )]
+    #[case("{{#repeat 2}}{{peek local=\"remaining\"}} {{/repeat}}", false, "1 0 ")]
+    #[case("{{#repeat 2}}{{peek local=name}} {{/repeat}}", false, "2 2 ")]
+    #[case("{{#repeat 2}}{{peek local=name}} {{/repeat}}", true, "  ")]
+    #[case(
+        "{{#repeat 2}}{{peek local=name}}{{this.xcount}} {{/repeat}}",
+        true,
+        "  "
+    )]
+    #[case(
+        "{{#repeat 2}}{{peek local=name}}{{@count}} {{/repeat}}",
+        true,
+        "22 22 "
+    )]
+    #[case("{{#repeat 2}}{{peek local=\"count\"}} {{/repeat}}", true, "2 2 ")]
+    fn block_locals(#[case] template: &str, #[case] lazy: bool, #[case] output: &str) {
+        let helper = match lazy {
+            true => RepeatHelper::default().with_lazy_locals(),
+            false => RepeatHelper::default(),
+        };
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(helper));
+        reg.register_helper("peek", Box::new(Peek));
+        let data = json!({"name": "count"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    struct Check;
+
+    impl HelperDef for Check {
+        fn call<'reg: 'rc, 'rc>(
+            &self,
+            h: &Helper<'rc>,
+            _: &'reg Handlebars<'reg>,
+            _: &'rc Context,
+            _: &mut RenderContext<'reg, 'rc>,
+            out: &mut dyn Output,
+        ) -> HelperResult {
+            match h.param(0).and_then(|p| p.value().as_u64()) {
+                Some(0) | None => Err(RenderErrorReason::Other("zero".into()).into()),
+                Some(n) => Ok(out.write(&n.to_string())?),
+            }
+        }
+    }
+
+    #[rstest]
+    #[case("{{#repeat values on_error=\"inline\" placeholder=\"n/a\" separator=\", \"}}{{check this}}{{/repeat}}", "1, n/a, 2")]
+    #[case(
+        "{{#repeat values on_error=\"inline\" separator=\", \"}}{{check this}}{{/repeat}}",
+        "1, zero, 2"
+    )]
+    #[case(
+        "{{#repeat values on_error=\"skip\" separator=\", \"}}{{check this}}{{/repeat}}",
+        "1, 2"
+    )]
+    #[case(
+        "{{#repeat values on_error=\"stop\" separator=\", \"}}{{check this}}{{/repeat}}",
+        "1"
+    )]
+    #[case(
+        "{{#repeat values on_error=\"skip\" prefix=\"[\" suffix=\"]\"}}x{{check this}}{{/repeat}}",
+        "[x1x2]"
+    )]
+    #[case(
+        "{{#repeat zeros on_error=\"skip\" prefix=\"[\" suffix=\"]\"}}x{{check this}}{{/repeat}}",
+        ""
+    )]
+    #[case("{{#repeat values on_error=\"skip\" wrap_every=1 wrap_open=\"(\" wrap_close=\")\"}}{{check this}}{{/repeat}}", "(1)(2)")]
+    #[case(
+        "{{#repeat values on_error=\"skip\" chomp=true}}\n{{check this}}\n{{/repeat}}",
+        "12"
+    )]
+    #[case("{{#repeat values on_error=\"skip\"}}{{#each ../values}}{{check this}}{{/each}};{{/repeat}}", "")]
+    #[case("{{#repeat values on_error=\"skip\"}}{{#with this}}{{check this}}{{/with}}{{@index}}{{/repeat}}", "10122")]
+    #[case(
+        "{{#repeat values on_error=\"skip\"}}{{check this}}{{@global_index}} {{/repeat}}|{{#repeat 1}}{{@global_index}}{{/repeat}}",
+        "10 22 |3"
+    )]
+    fn on_error(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("check", Box::new(Check));
+
+        let data = json!({"values": [1, 0, 2], "zeros": [0, 0]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 1 on_error=\"inline\"}}{{missing}}{{/repeat}}",
+        "Failed to access variable in strict mode Some(&quot;missing&quot;)"
+    )]
+    #[case(
+        "{{#repeat 1 on_error=\"inline\" escape=\"json\"}}{{missing}}{{/repeat}}",
+        "Failed to access variable in strict mode Some(\\\"missing\\\")"
+    )]
+    #[case(
+        "{{#repeat 1 on_error=\"inline\" escape=\"none\"}}{{missing}}{{/repeat}}",
+        "Failed to access variable in strict mode Some(\"missing\")"
+    )]
+    #[case("{{#repeat 3 on_error=\"skip\"}}{{counter \"c\"}}{{#if @last}}{{missing}}{{/if}}{{/repeat}}{{counter \"c\"}}", "124")]
+    #[case("{{#repeat 2 on_error=\"skip\"}}{{#each list}}{{#with this}}{{missing}}{{/with}}{{/each}}{{/repeat}}{{#repeat 1}}{{@index}}{{/repeat}}", "0")]
+    fn on_error_strict(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.set_strict_mode(true);
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("counter", Box::new(CounterHelper));
+
+        let data = json!({"list": [1]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn on_error_default() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("check", Box::new(Check));
+
+        let template = "{{#repeat values}}{{check this}}{{/repeat}}";
+        let err = reg
+            .render_template(template, &json!({"values": [1, 0]}))
+            .unwrap_err();
+        assert!(matches!(err.reason(), RenderErrorReason::Other(e) if e == "zero"));
+    }
+
+    #[test]
+    fn wrong_on_error() {
+        let err = render("{{#repeat 1 on_error=\"ignore\"}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"on_error".to_string()) && b == &"skip, stop or inline".to_string()));
+    }
+
+    #[rstest]
+    #[case("{{#repeat}}{{@index}}{{/repeat}}", "012")]
+    #[case("{{#repeat start=1 separator=\",\"}}{{@index}}{{/repeat}}", "1,2,3")]
+    #[case("{{#repeat 1}}{{@index}}{{/repeat}}", "0")]
+    #[case("{{#repeat count=2}}{{@index}}{{/repeat}}", "01")]
+    fn default_count(#[case] template: &str, #[case] output: &str) {
+        let helper = RepeatHelper::default().with_default(3);
+        assert_eq!(render_with(helper, template, json!(0)).unwrap(), output);
+    }
+
+    #[test]
+    fn default_count_zero() {
+        let helper = RepeatHelper::default().with_default(0);
+        let output = render_with(helper, "{{#repeat}}x{{else}}bar{{/repeat}}", json!(0));
+        assert_eq!(output.unwrap(), "bar");
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@rindex}} retries left {{/repeat}}",
+        "2 retries left 1 retries left 0 retries left "
+    )]
+    #[case(
+        "{{#repeat 3 reverse=true}}{{@index}}:{{@rindex}} {{/repeat}}",
+        "2:0 1:1 0:2 "
+    )]
+    #[case(
+        "{{#repeat 3 start=5 step=2}}{{@index}}:{{@rindex}} {{/repeat}}",
+        "5:2 7:1 9:0 "
+    )]
+    #[case("{{#repeat 2 5}}{{@rindex}}{{/repeat}}", "210")]
+    #[case("{{#repeat 5 skip=1 limit=2}}{{@rindex}}{{/repeat}}", "32")]
+    fn rindex(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 4}}{{#if @even}}e{{/if}}{{#if @odd}}o{{/if}}{{/repeat}}",
+        "eoeo"
+    )]
+    #[case(
+        "{{#repeat 3 reverse=true}}{{@index}}{{#if @even}}e{{/if}}{{/repeat}}",
+        "2e10e"
+    )]
+    #[case(
+        "{{#repeat 3 step=2}}{{@index}}{{#if @odd}}o{{/if}}{{/repeat}}",
+        "02o4"
+    )]
+    #[case(
+        "{{#repeat 5 skip=1 limit=2}}{{@index}}{{#if @odd}}o{{/if}}{{/repeat}}",
+        "1o2"
+    )]
+    fn odd_even(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 start=1}}step {{@index}} of {{@count}} {{/repeat}}",
+        "step 1 of 3 step 2 of 3 step 3 of 3 "
+    )]
+    #[case("{{#repeat 2 7 step=2}}{{@count}}{{/repeat}}", "333")]
+    #[case("{{#repeat 9 skip=2 limit=2}}{{@count}}{{/repeat}}", "99")]
+    #[case("{{#repeat 9 max=2 clamp=true}}{{@count}}{{/repeat}}", "22")]
+    fn count_local(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(1, "-")]
+    #[case(2, "--")]
+    #[case(4, "-mm-")]
+    fn middle(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count}}{{#if @middle}}m{{else}}-{{/if}}{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3}}{{@index}}:{{@index1}} {{/repeat}}", "0:1 1:2 2:3 ")]
+    #[case("{{#repeat 2 start=10 step=5}}{{@index1}} {{/repeat}}", "11 16 ")]
+    #[case("{{#repeat 2 reverse=true}}{{@index1}} {{/repeat}}", "2 1 ")]
+    fn index1(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@remaining}} slots remaining {{/repeat}}",
+        "2 slots remaining 1 slots remaining 0 slots remaining "
+    )]
+    #[case("{{#repeat 3 reverse=true}}{{@remaining}}{{/repeat}}", "210")]
+    #[case("{{#repeat 9 skip=2 limit=3}}{{@remaining}}{{/repeat}}", "210")]
+    #[case("{{#repeat 3 start=5}}{{@remaining}}{{/repeat}}", "210")]
+    #[case(
+        "{{#repeat 5 limit=2}}{{@remaining}}:{{@rindex}} {{/repeat}}",
+        "1:4 0:3 "
+    )]
+    #[case(
+        "{{#repeat 5 skip=1 limit=2 reverse=true}}{{@remaining}}:{{@index}} {{/repeat}}",
+        "1:3 0:2 "
+    )]
+    fn remaining(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 4}}{{@progress}}:{{@percent}} {{/repeat}}",
+        "0.25:25 0.5:50 0.75:75 1.0:100 "
+    )]
+    #[case("{{#repeat 3}}{{@percent}} {{/repeat}}", "33 66 100 ")]
+    #[case("{{#repeat 1}}{{@progress}}:{{@percent}}{{/repeat}}", "1.0:100")]
+    #[case("{{#repeat 4 reverse=true}}{{@percent}} {{/repeat}}", "25 50 75 100 ")]
+    #[case("{{#repeat 10 skip=4 limit=2}}{{@percent}} {{/repeat}}", "50 60 ")]
+    fn progress(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "bar")]
+    #[case(3, "row-even row-odd row-even ")]
+    fn parity(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count}}row-{{@parity}} {{else}}bar{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3}}{{@index0}} {{/repeat}}", "0 1 2 ")]
+    #[case(
+        "{{#repeat 120 skip=6 limit=2 start=1}}{{@index0}} {{/repeat}}",
+        "007 008 "
+    )]
+    #[case("{{#repeat 10 skip=8}}{{@index0}} {{/repeat}}", "08 09 ")]
+    #[case("{{#repeat 2 start=100}}{{@index0}} {{/repeat}}", "100 101 ")]
+    fn index0(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 step=4 format=\"REG_%02X\"}}{{@index_fmt}} {{/repeat}}",
+        "REG_00 REG_04 REG_08 "
+    )]
+    #[case(
+        "{{#repeat 2 start=9 format=\"%04b\"}}{{@index_fmt}} {{/repeat}}",
+        "1001 1010 "
+    )]
+    #[case("{{#repeat 2}}[{{@index_fmt}}]{{/repeat}}", "[][]")]
+    fn format(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2 format=\"%s\"}}x{{/repeat}}")]
+    #[case("{{#repeat 2 format=4}}x{{/repeat}}")]
+    #[case("{{#repeat 2 format=\"%0100000000d\"}}x{{/repeat}}")]
+    fn wrong_format(#[case] template: &str) {
+        let err = render(template, 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"format".to_string()) && b == &"printf-style format".to_string()));
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3}}({{@alpha}}) {{/repeat}}", "(a) (b) (c) ")]
+    #[case("{{#repeat 2 start=25}}{{@ALPHA}} {{/repeat}}", "Z AA ")]
+    fn alpha(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 4}}{{@roman}} {{/repeat}}", "i ii iii iv ")]
+    #[case(
+        "{{#repeat 2 start=8}}Appendix {{@ROMAN}} {{/repeat}}",
+        "Appendix IX Appendix X "
+    )]
+    #[case("{{#repeat 2 start=3998}}[{{@roman}}]{{/repeat}}", "[mmmcmxcix][]")]
+    fn roman(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 4}}{{@ordinal}} {{/repeat}}", "1st 2nd 3rd 4th ")]
+    #[case("{{#repeat 3 start=10}}{{@ordinal}} {{/repeat}}", "11th 12th 13th ")]
+    fn ordinal(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 2 ident_prefix=\"item\"}}int {{@ident}}; {{/repeat}}",
+        "int item_0; int item_1; "
+    )]
+    #[case("{{#repeat 2}}{{@ident}} {{/repeat}}", "_0 _1 ")]
+    #[case(
+        "{{#repeat 1 start=3 ident_prefix=\"3-d.x\"}}{{@ident}}{{/repeat}}",
+        "_3_d_x_3"
+    )]
+    fn ident(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3 sep=\",\"}}{{@index}}{{@sep}}\n{{/repeat}}",
+        "0,\n1,\n2\n"
+    )]
+    #[case(
+        "{{#repeat 5 sep=\";\" skip=1 limit=2}}{{@index}}{{@sep}}{{/repeat}}",
+        "1;2"
+    )]
+    #[case("{{#repeat 2}}[{{@sep}}]{{/repeat}}", "[][]")]
+    fn sep(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@prev}}<{{@index}}>{{@next}} {{/repeat}}",
+        "<0>1 0<1>2 1<2> "
+    )]
+    #[case(
+        "{{#repeat 3 reverse=true}}{{@prev}}<{{@index}}>{{@next}} {{/repeat}}",
+        "<2>1 2<1>0 1<0> "
+    )]
+    #[case(
+        "{{#repeat 3 start=10 step=5}}{{@prev}}<{{@index}}>{{@next}} {{/repeat}}",
+        "<10>15 10<15>20 15<20> "
+    )]
+    #[case(
+        "{{#repeat 9 skip=4 limit=1}}{{@prev}}<{{@index}}>{{@next}}{{/repeat}}",
+        "3<4>5"
+    )]
+    #[case(
+        "{{#repeat 1}}{{#if @prev}}p{{/if}}{{#if @next}}n{{/if}}{{/repeat}}",
+        ""
+    )]
+    fn prev_next(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 5 bucket_size=2}}{{@bucket}}{{#if @bucket_first}}f{{/if}}{{#if @bucket_last}}l{{/if}} {{/repeat}}", "0f 0l 1f 1l 2fl ")]
+    #[case("{{#repeat 4 bucket_size=2 reverse=true}}{{@bucket}}{{#if @bucket_first}}f{{/if}} {{/repeat}}", "1 1f 0 0f ")]
+    #[case("{{#repeat 3 bucket_size=1}}{{@bucket}}{{/repeat}}", "012")]
+    #[case("{{#repeat 3 start=10 bucket_size=5}}{{@bucket}}{{/repeat}}", "000")]
+    #[case("{{#repeat 2}}[{{@bucket}}]{{/repeat}}", "[][]")]
+    fn bucket(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_bucket_size() {
+        let err = render("{{#repeat 3 bucket_size=0}}x{{/repeat}}", 0).unwrap_err();
+        assert!(matches!(
+            err.reason(),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                a,
+                b
+            ) if (a == &"bucket_size".to_string()) && b == &"non-zero u64".to_string()));
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2}}{{@depth}}{{/repeat}}", "00")]
+    #[case(
+        "{{#repeat 2}}{{@depth}}({{#repeat 2}}{{@depth}}{{/repeat}}){{/repeat}}",
+        "0(11)0(11)"
+    )]
+    #[case(
+        "{{#repeat 1}}{{#each list}}{{#repeat 1}}{{@depth}}{{/repeat}}{{/each}}{{/repeat}}",
+        "11"
+    )]
+    #[case("{{#repeat 1}}{{#repeat 1}}{{#repeat 1}}{{@depth}}{{/repeat}}{{/repeat}}{{@depth}}{{/repeat}}{{#repeat 1}}{{@depth}}{{/repeat}}", "200")]
+    fn depth(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn depth_after_error() {
+        let template = "{{#repeat 1}}{{#repeat 1}}{{#repeat foo}}{{/repeat}}{{/repeat}}{{/repeat}}";
+        assert_fails(
+            render(template, 0),
+            RenderErrorReason::ParamTypeMismatchForName(
+                "repeat",
+                "0".to_string(),
+                "u64".to_string(),
+            )
+            .into(),
+        );
+        assert_eq!(
+            render("{{#repeat 1}}{{@depth}}{{/repeat}}", 0).unwrap(),
+            "0"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 2}}{{#repeat 2}}{{lookup @outer \"index\"}}{{@index}} {{/repeat}}{{/repeat}}",
+        "00 01 10 11 "
+    )]
+    #[case("{{#repeat 2}}{{#each list}}{{#repeat 1}}{{lookup @outer \"index\"}}{{#if (lookup @outer \"last\")}}!{{/if}}{{/repeat}}{{/each}}{{/repeat}}", "001!1!")]
+    #[case("{{#repeat 2 start=5}}{{#repeat 1}}{{#repeat 1}}{{lookup (lookup @outer \"outer\") \"index\"}}{{/repeat}}{{/repeat}}{{/repeat}}", "56")]
+    #[case("{{#repeat 2}}[{{@outer}}]{{/repeat}}", "[][]")]
+    #[case(
+        "{{#repeat 2}}{{#repeat 2}}{{@outer_index}}{{@index}} {{/repeat}}{{/repeat}}",
+        "00 01 10 11 "
+    )]
+    #[case("{{#repeat 2}}{{#each list}}{{#repeat 1}}{{@outer_alpha}}{{#if @outer_last}}!{{/if}}{{/repeat}}{{/each}}{{/repeat}}", "aab!b!")]
+    #[case("{{#repeat 2 start=5}}{{#repeat 1}}{{#repeat 1}}{{@outer_outer_index}}{{/repeat}}{{/repeat}}{{/repeat}}", "56")]
+    #[case("{{#repeat 2}}[{{@outer_index}}]{{/repeat}}", "[][]")]
+    fn outer(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2}}{{> roman}}{{/repeat}}", "iii")]
+    #[case("{{#repeat 2}}{{> nested}}{{/repeat}}", "iii")]
+    #[case("{{#repeat 2}}{{> (lookup names 0)}}{{/repeat}}", "iii")]
+    #[case(
+        "{{#repeat 2}}{{#repeat 1}}{{lookup @rep_outer \"alpha\"}}{{/repeat}}{{/repeat}}",
+        "ab"
+    )]
+    #[case("{{#repeat 3 until=\"eq @rep_rindex 0\"}}{{@i}}{{/repeat}}", "01")]
+    #[case(
+        "{{#repeat 2}}{{@rep_ordinal}},{{@rep_roman}}{{/repeat}}",
+        "1st,i2nd,ii"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#repeat 1}}{{#repeat 1}}{{@rep_outer_outer_index}}{{/repeat}}{{/repeat}}{{/repeat}}",
+        "01"
+    )]
+    #[case("{{#repeat 2}}{{@rep_ordinal}}{{@i}}{{/repeat}}", "1st02nd1")]
+    fn named_locals(
+        #[case] template: &str,
+        #[case] output: &str,
+        #[values(false, true)] lazy: bool,
+    ) {
+        let mut helper = RepeatHelper::default()
+            .with_local_prefix("rep_")
+            .with_local_name("index", "i");
+        if lazy {
+            helper = helper.with_lazy_locals();
+        }
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(helper));
+        reg.register_partial("roman", "{{@rep_roman}}").unwrap();
+        reg.register_partial("nested", "{{> roman}}").unwrap();
+        let data = json!({"names": ["roman"]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2 label=\"row\"}}{{#repeat 2 label=\"col\"}}({{lookup @row \"index\"}},{{lookup @col \"index\"}}){{/repeat}}{{/repeat}}", "(0,0)(0,1)(1,0)(1,1)")]
+    #[case("{{#repeat 2 label=\"a\"}}{{#each list}}{{#repeat 1}}{{#repeat 1}}{{lookup @a \"index\"}}{{/repeat}}{{/repeat}}{{/each}}{{/repeat}}", "0011")]
+    #[case("{{#repeat 1 label=\"a\"}}{{#repeat 1 start=3 label=\"a\"}}{{lookup @a \"index\"}}{{/repeat}}{{/repeat}}", "3")]
+    #[case(
+        "{{#repeat 1 label=\"a\"}}{{#repeat 2}}{{/repeat}}{{lookup @a \"last\"}}{{/repeat}}",
+        "true"
+    )]
+    #[case("{{#repeat 2}}[{{@a}}]{{/repeat}}", "[][]")]
+    #[case("{{#repeat 2 label=\"row\"}}{{#repeat 2 label=\"col\"}} {{@row_index}},{{@col_index}} {{/repeat}}{{/repeat}}", " 0,0  0,1  1,0  1,1 ")]
+    #[case("{{#repeat 2 label=\"a\"}}{{#each list}}{{#repeat 1}}{{@a_alpha}}{{/repeat}}{{/each}}{{/repeat}}", "aabb")]
+    #[case("{{#repeat 1 label=\"a\"}}{{#repeat 1 start=3 label=\"a\"}}{{@a_index}}{{/repeat}}{{/repeat}}", "3")]
+    #[case("{{#repeat 2 label=\"a\"}}[{{@a_last}}]{{/repeat}}", "[false][true]")]
+    fn label(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2}}{{@global_index}}{{/repeat}}", "01")]
+    #[case(
+        "{{#repeat 2}}{{#repeat 2}}{{@global_index}}{{/repeat}}{{/repeat}}",
+        "0123"
+    )]
+    #[case(
+        "{{#repeat 2}}<{{@global_index}}>{{#repeat 2}}{{@global_index}}{{/repeat}}{{/repeat}}",
+        "<0>01<2>23"
+    )]
+    #[case(
+        "{{#repeat 1}}{{#each list}}{{#repeat 1}}{{@global_index}}{{/repeat}}{{/each}}{{/repeat}}",
+        "01"
+    )]
+    #[case(
+        "{{#repeat 2}}{{/repeat}}{{#repeat 2}}{{@global_index}}{{/repeat}}",
+        "23"
+    )]
+    #[case(
+        "{{#repeat 2}}{{@global_index}}{{/repeat}}|{{#repeat 2}}{{@global_index}}{{/repeat}}",
+        "01|23"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#repeat 0}}{{/repeat}}{{@global_index}}{{/repeat}}|{{#repeat 1 raw=true}}x{{/repeat}}{{#repeat 1}}{{@global_index}}{{/repeat}}",
+        "01|x3"
+    )]
+    fn global_index(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 2 counter=\"fig\"}}Figure {{@counter}}. {{/repeat}}\nFigure {{counter \"fig\"}}.\n{{#repeat 1 counter=\"fig\"}}Figure {{@counter}}.{{/repeat}}", "Figure 1. Figure 2. \nFigure 3.\nFigure 4.")]
+    #[case("{{#repeat 2 counter=\"a\"}}{{@counter}}{{#repeat 2 counter=\"b\"}}{{@counter}}{{/repeat}}{{/repeat}}{{counter \"a\"}}", "1122343")]
+    #[case("{{#each list}}{{#repeat 1 counter=\"a\"}}{{@counter}}{{/repeat}}{{/each}}{{counter \"a\"}}", "123")]
+    #[case("{{counter \"a\"}}{{> part}}{{> part}}{{counter \"a\"}}", "1234")]
+    #[case("{{counter \"x\"}}{{#repeat 2}}[{{@counter}}]{{/repeat}}", "1[][]")]
+    #[case("{{#repeat 1}}{{> part}}{{> part}}{{/repeat}}{{counter \"a\"}}", "123")]
+    #[case(
+        "{{#*inline \"fig\"}}Fig {{counter \"f\"}} {{/inline}}{{#repeat 3}}{{> fig}}{{/repeat}}",
+        "Fig 1 Fig 2 Fig 3 "
+    )]
+    #[case(
+        "{{#*inline \"fig\"}}Fig {{counter \"f\"}} {{/inline}}{{counter \"f\"}} {{> fig}}{{> fig}}",
+        "1 Fig 2 Fig 3 "
+    )]
+    #[case(
+        "{{#repeat 2 on_error=\"skip\"}}{{> part}}{{/repeat}}{{counter \"a\"}}",
+        "123"
+    )]
+    #[case(
+        "{{#repeat 2 escape=\"json\"}}{{> part}}{{/repeat}}{{counter \"a\"}}",
+        "123"
+    )]
+    #[case(
+        "{{#repeat 2 on_error=\"inline\"}}{{> part}}{{/repeat}}{{> part}}",
+        "123"
+    )]
+    #[case("{{#repeat 1}}{{> part}}{{/repeat}}{{> part}}{{#repeat 1 counter=\"a\"}}{{@counter}}{{/repeat}}", "123")]
+    fn counter(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("counter", Box::new(CounterHelper));
+        reg.register_partial("part", "{{#repeat 1 counter=\"a\"}}{{@counter}}{{/repeat}}")
+            .unwrap();
+
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+
+        // Each render starts afresh.
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_counter() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("counter", Box::new(CounterHelper));
+        assert_fails(
+            reg.render_template("{{counter}}", &()),
+            RenderErrorReason::ParamNotFoundForIndex("counter", 0).into(),
+        );
+        assert_fails(
+            reg.render_template("{{counter 1}}", &()),
+            RenderErrorReason::ParamTypeMismatchForName(
+                "counter",
+                "0".to_string(),
+                "string".to_string(),
+            )
+            .into(),
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_local() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+
+        let render = |template: &str| reg.render_template(template, &()).unwrap();
+        let values = |template: &str| -> Vec<f64> {
+            render(template)
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse().unwrap())
+                .collect()
+        };
+
+        let seven = values("{{#repeat 5 seed=7}}{{@random}},{{/repeat}}");
+        assert_eq!(seven.len(), 5);
+        assert!(seven.iter().all(|v| (0.0..1.0).contains(v)));
+        assert_ne!(seven[0], seven[1]);
+
+        // Stable across renders and tied to the index, not the repetition.
+        assert_eq!(seven, values("{{#repeat 5 seed=7}}{{@random}},{{/repeat}}"));
+        assert_eq!(
+            seven[2..],
+            values("{{#repeat 2 5 seed=7}}{{@random}},{{/repeat}}")[..]
+        );
+        assert_ne!(seven, values("{{#repeat 5 seed=8}}{{@random}},{{/repeat}}"));
+        assert_eq!(
+            values("{{#repeat 2}}{{@random}},{{/repeat}}"),
+            values("{{#repeat 2 seed=0}}{{@random}},{{/repeat}}"),
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+
+        let render = |template: &str| reg.render_template(template, &()).unwrap();
+        let seeded = render("{{#repeat 3 uuid_seed=1}}{{@uuid}},{{/repeat}}");
+        let uuids: Vec<&str> = seeded.split(',').filter(|u| !u.is_empty()).collect();
+        assert_eq!(uuids.len(), 3);
+        assert_ne!(uuids[0], uuids[1]);
+        for uuid in &uuids {
+            assert_eq!(uuid.len(), 36);
+            assert_eq!(&uuid[14..15], "4");
+        }
+
+        assert_eq!(
+            seeded,
+            render("{{#repeat 3 uuid_seed=1}}{{@uuid}},{{/repeat}}")
+        );
+        assert_ne!(
+            seeded,
+            render("{{#repeat 3 uuid_seed=2}}{{@uuid}},{{/repeat}}")
+        );
+
+        let fresh = "{{#repeat 3}}{{@uuid}},{{/repeat}}";
+        assert_ne!(render(fresh), render(fresh));
+    }
+
+    #[rstest]
+    #[case("{{#repeat 3}}{{@index_str}}{{/repeat}}", "012")]
+    #[case(
+        "{{#repeat 1 start=9007199254740993}}{{@index_str}}{{/repeat}}",
+        "9007199254740993"
+    )]
+    #[case(
+        "{{#repeat 18446744073709551614 18446744073709551615}}{{@index_str}}={{@index}}{{/repeat}}",
+        "18446744073709551614=18446744073709551614"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#if (eq @index_str \"1\")}}one{{/if}}{{/repeat}}",
+        "one"
+    )]
+    fn index_str(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "i18n")]
+    #[rstest]
+    #[case("{{#repeat 1 start=1234567}}{{@index_loc}}{{/repeat}}", "1,234,567")]
+    #[case(
+        "{{#repeat 1 start=1000 locale=\"de\"}}{{@index_loc}}{{/repeat}}",
+        "1.000"
+    )]
+    #[case(
+        "{{#repeat 1 start=1000 locale=\"fr-FR\"}}{{@index_loc}}{{/repeat}}",
+        "1\u{202f}000"
+    )]
+    #[case(
+        "{{#repeat 2 start=99999 locale=\"en_IN\"}}{{@index_loc}} {{/repeat}}",
+        "99,999 1,00,000 "
+    )]
+    fn index_loc(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "i18n")]
+    #[rstest]
+    #[case(
+        "{{#repeat 1 locale=\"xx\"}}{{/repeat}}",
+        RenderErrorReason::HashTypeMismatchForName("repeat", "locale".to_string(), "known locale".to_string()).into()
+    )]
+    #[case(
+        "{{#repeat 1 locale=1}}{{/repeat}}",
+        RenderErrorReason::HashTypeMismatchForName("repeat", "locale".to_string(), "known locale".to_string()).into()
+    )]
+    fn wrong_locale(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#each list}}{{#repeat 2}}{{@index}}{{@i}}{{/repeat}}{{/each}}",
+        "00011011"
+    )]
+    #[case("{{#repeat 3}}{{@i}}{{@rep_last}}{{/repeat}}", "0false1false2true")]
+    #[case(
+        "{{#repeat 2 label=\"row\"}}{{lookup @row \"index\"}}{{/repeat}}",
+        "01"
+    )]
+    #[case(
+        "{{#repeat 1}}{{#repeat 1}}{{lookup @rep_outer \"index\"}}{{/repeat}}{{/repeat}}",
+        "0"
+    )]
+    fn local_names(#[case] template: &str, #[case] output: &str) {
+        let helper = RepeatHelper::default()
+            .with_local_prefix("rep_")
+            .with_local_name("index", "i");
+
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(helper));
+        let data = json!({"list": [0, 1]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn hash_local() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+
+        let render = |template: &str| reg.render_template(template, &()).unwrap();
+        let hashes = render("{{#repeat 3 seed=7}}{{@hash}} {{/repeat}}");
+        let hashes: Vec<&str> = hashes.split_whitespace().collect();
+        assert_eq!(hashes.len(), 3);
+        assert!(hashes.iter().all(|h| h.len() == 16));
+        assert_ne!(hashes[0], hashes[1]);
+
+        assert_eq!(
+            render("{{#repeat 1 start=2 seed=7}}{{@hash}}{{/repeat}}"),
+            hashes[2]
+        );
+        assert_ne!(
+            render("{{#repeat 1 seed=8}}{{@hash}}{{/repeat}}"),
+            hashes[0]
+        );
+        assert_eq!(
+            render("{{#repeat 1}}{{@hash}}{{/repeat}}"),
+            render("{{#repeat 1 seed=0}}{{@hash}}{{/repeat}}")
+        );
+    }
+
+    #[rstest]
+    #[case(1, "only ")]
+    #[case(2, "start end ")]
+    #[case(4, "start middle middle end ")]
+    fn phase(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count}}{{@phase}} {{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#repeat 5 group_size=2}}{{@group_index}}{{#if @group_first}}f{{/if}}{{#if @group_last}}l{{/if}} {{/repeat}}", "0f 0l 1f 1l 2fl ")]
+    #[case("{{#repeat 4 group_size=2 reverse=true}}{{@index}}{{@group_index}}{{#if @group_first}}f{{/if}} {{/repeat}}", "30f 20 11f 01 ")]
+    #[case("{{#repeat 5 group_size=2 skip=1}}{{@index}}{{@group_index}}{{#if @group_last}}l{{/if}} {{/repeat}}", "10 20l 31 41l ")]
+    #[case(
+        "{{#repeat 5 group_size=2 limit=3}}{{#if @group_last}}l{{/if}}{{/repeat}}",
+        "ll"
+    )]
+    #[case("{{#repeat 2}}[{{@group_index}}]{{/repeat}}", "[][]")]
+    fn group(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(render(template, 0).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_group_size() {
+        assert_fails(
+            render("{{#repeat 3 group_size=0}}x{{/repeat}}", 0),
+            RenderErrorReason::HashTypeMismatchForName(
+                "repeat",
+                "group_size".to_string(),
+                "non-zero u64".to_string(),
+            )
+            .into(),
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@index}}{{else after}}|{{@rendered}}/{{@count}}{{/repeat}}",
+        "012|3/3"
+    )]
+    #[case("{{#repeat 3 limit=2}}{{@index}}{{else after}}|{{@rendered}}/{{@count}}{{else}}none{{/repeat}}", "01|2/3")]
+    #[case(
+        "{{#repeat 0}}{{@index}}{{else after}}|{{@count}}{{else}}none{{/repeat}}",
+        "none"
+    )]
+    #[case("{{#repeat 0}}{{@index}}{{else after}}|{{@count}}{{/repeat}}", "")]
+    #[case(
+        "{{#repeat 2 separator=\",\" suffix=\"]\"}}{{@index}}{{else after}}!{{/repeat}}",
+        "0,1]!"
+    )]
+    #[case("{{#repeat 0}}x{{else if name}}{{name}}{{/repeat}}", "foo")]
+    #[case("{{#repeat 1}}{{#repeat 2}}{{@index}}{{else after}}{{@count}}{{/repeat}}{{@count}}{{/repeat}}", "0121")]
+    fn after(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"name": "foo"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@index}}{{else before}}{{@count}}:{{/repeat}}",
+        "3:012"
+    )]
+    #[case("{{#repeat 0}}{{@index}}{{else before}}{{@count}}:{{/repeat}}", "")]
+    #[case(
+        "{{#repeat 0}}{{@index}}{{else before}}header{{else}}none{{/repeat}}",
+        "none"
+    )]
+    #[case(
+        "{{#repeat 2 prefix=\"[\"}}{{@index}}{{else after}}>{{else before}}<{{/repeat}}",
+        "<[01>"
+    )]
+    #[case(
+        "{{#repeat 2}}{{@index}}{{else before}}{{@index}}{{@first}}{{/repeat}}",
+        "01"
+    )]
+    fn before(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0, "none")]
+    #[case(1, "<one 0:true>")]
+    #[case(2, "<01>")]
+    fn single(#[case] count: u64, #[case] output: &str) {
+        let template = "{{#repeat count}}{{@index}}{{else single}}one {{@index}}:{{@last}}{{else before}}<{{else after}}>{{else}}none{{/repeat}}";
+        assert_eq!(render(template, count).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 3}}{{@index}}{{#sep}}<{{@index}}>{{/sep}}{{/repeat}}",
+        "0<0>1<1>2"
+    )]
+    #[case("{{#repeat 1}}{{@index}}{{#sep}},{{/sep}}{{/repeat}}", "0")]
+    #[case("{{#repeat 3 limit=2}}{{@index}}{{#sep}},{{/sep}}{{/repeat}}", "0,1")]
+    #[case(
+        "{{#repeat 2}}{{#each list}}{{this}}{{/each}}{{#sep}};{{/sep}}{{/repeat}}",
+        "12;12"
+    )]
+    #[case("{{#repeat 2}}{{#repeat 2}}{{@index}}{{#sep}},{{/sep}}{{/repeat}}{{#sep}};{{/sep}}{{/repeat}}", "0,1;0,1")]
+    #[case(
+        "{{#repeat 2}}{{@index}}{{else before}}{{#sep}}x{{/sep}}{{/repeat}}",
+        "01"
+    )]
+    fn sep_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("sep", Box::new(SepHelper));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn sep_outside() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("sep", Box::new(SepHelper));
+        let err = reg.render_template("{{#sep}},{{/sep}}", &()).unwrap_err();
+        assert!(matches!(err.reason(), RenderErrorReason::NestedError(e)
+            if e.downcast_ref::<Error>() == Some(&Error::OutsideRepeat("sep"))));
+    }
+
+    #[rstest]
+    #[case(json!({"items": 2, "fallback": "f"}), "xx")]
+    #[case(json!({"items": 0, "fallback": "f"}), "f")]
+    #[case(json!({"items": 0, "other": true}), "o")]
+    #[case(json!({"items": 0}), "none")]
+    fn else_chain(#[case] data: JsonValue, #[case] output: &str) {
+        let template = "{{#repeat items}}x{{else after}}{{else if fallback}}{{fallback}}{{else if other}}o{{else}}none{{/repeat}}";
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 5}}{{@index}}{{#if (eq @index 2)}}{{break}}{{/if}};{{/repeat}}",
+        "0;1;2"
+    )]
+    #[case(
+        "{{#repeat 3 separator=\",\" suffix=\"]\"}}{{@index}}{{break}}{{/repeat}}",
+        "0]"
+    )]
+    #[case("{{#repeat 3 chomp=true}}\n{{@index}}{{break}}\n{{/repeat}}", "0")]
+    #[case(
+        "{{#repeat 3}}{{#each list}}{{this}}{{break}}{{/each}}{{/repeat}}",
+        "1"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#repeat 3}}{{@index}}{{break}}{{/repeat}};{{/repeat}}",
+        "0;0;"
+    )]
+    #[case("{{#repeat 3}}{{> part}}{{/repeat}}", "<")]
+    #[case("{{#repeat 2}}{{@index}}{{else after}}a{{break}}b{{/repeat}}", "01a")]
+    fn break_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_partial("part", "<{{break}}>").unwrap();
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn break_outside() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("break", Box::new(BreakHelper));
+        let err = reg.render_template("{{break}}", &()).unwrap_err();
+        assert!(matches!(err.reason(), RenderErrorReason::NestedError(e)
+            if e.downcast_ref::<Error>() == Some(&Error::OutsideRepeat("break"))));
+    }
+
+    #[test]
+    fn break_nested_render() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_helper(
+            "nested",
+            Box::new(
+                |_: &Helper<'_>,
+                 r: &Handlebars<'_>,
+                 _: &Context,
+                 _: &mut RenderContext<'_, '_>,
+                 out: &mut dyn Output|
+                 -> HelperResult {
+                    let err = r.render_template("{{break}}", &()).unwrap_err();
+                    out.write(&err.reason().to_string())?;
+                    Ok(())
+                },
+            ),
+        );
+
+        // A render within a helper has no enclosing repeat block.
+        let output = reg.render_template("{{#repeat 1}}{{nested}}{{/repeat}}", &());
+        assert_eq!(
+            output.unwrap(),
+            "Nested error: break helper used outside a repeat block"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat 4}}{{@index}}{{#if @odd}}{{continue}}{{/if}};{{/repeat}}",
+        "0;12;3"
+    )]
+    #[case(
+        "{{#repeat 3 separator=\",\"}}{{@index}}{{continue}}!{{/repeat}}",
+        "0,1,2"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#repeat 2}}{{@index}}{{continue}}!{{/repeat}};{{/repeat}}",
+        "01;01;"
+    )]
+    #[case(
+        "{{#repeat 3}}{{#each list}}{{this}}{{continue}}{{/each}}{{/repeat}}",
+        "111"
+    )]
+    #[case("{{#repeat 3}}{{@index}}{{continue}}{{break}}{{/repeat}}", "012")]
+    fn continue_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_helper("continue", Box::new(ContinueHelper));
+        let data = json!({"list": [1, 2]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#repeat items}}{{acc \"t\" price}}{{acc \"t\"}} {{/repeat}}={{acc \"t\"}}",
+        "3 7 =7"
+    )]
+    #[case(
+        "{{#repeat items}}{{acc \"t\" price}}{{else after}}{{lookup @acc \"t\"}}{{/repeat}}",
+        "7"
+    )]
+    #[case("{{#repeat 3}}{{acc \"t\" 0.5}}{{/repeat}}{{acc \"t\"}}", "1.5")]
+    #[case("{{#repeat 2}}{{acc \"t\" -3}}{{/repeat}}{{acc \"t\"}}", "-6")]
+    #[case("{{#repeat 2}}{{#repeat 2}}{{acc \"t\" 1}}{{/repeat}}{{acc \"t\" 10}}{{/repeat}}{{acc \"t\"}}", "20")]
+    #[case(
+        "{{#repeat 2}}{{#repeat 2}}{{acc \"i\" 1}}{{/repeat}}{{acc \"i\"}}{{/repeat}}",
+        "22"
+    )]
+    #[case("{{#repeat 0}}{{acc \"t\" 1}}{{/repeat}}[{{acc \"t\"}}]", "[]")]
+    #[case("[{{acc \"t\"}}]", "[]")]
+    fn acc(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("acc", Box::new(AccHelper));
+        let data = json!({"items": [{"price": 3}, {"price": 4}]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+
+        // Each render starts afresh.
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn acc_collect() {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("acc", Box::new(AccHelper));
+        let template = "{{#repeat 3}}{{acc \"n\" @alpha collect=true}}{{else after}}{{#each (lookup @acc \"n\")}}{{this}};{{/each}}{{/repeat}}";
+        assert_eq!(reg.render_template(template, &()).unwrap(), "a;b;c;");
+    }
+
+    #[rstest]
+    #[case(
+        "{{acc \"t\" 1}}",
+        Error::OutsideRepeat("acc").into()
+    )]
+    #[case(
+        "{{#repeat 1}}{{acc \"t\" \"x\"}}{{/repeat}}",
+        RenderErrorReason::ParamTypeMismatchForName("acc", "1".to_string(), "number".to_string()).into()
+    )]
+    #[case(
+        "{{#repeat 2}}{{acc \"t\" 1 collect=true}}{{acc \"t\" 1}}{{/repeat}}",
+        RenderErrorReason::ParamTypeMismatchForName("acc", "1".to_string(), "number".to_string()).into()
+    )]
+    #[case(
+        "{{#repeat 1}}{{acc 1 1}}{{/repeat}}",
+        RenderErrorReason::ParamTypeMismatchForName("acc", "0".to_string(), "string".to_string()).into()
+    )]
+    #[case(
+        "{{#repeat 1}}{{acc}}{{/repeat}}",
+        RenderErrorReason::ParamNotFoundForIndex("acc", 0).into()
+    )]
+    fn wrong_acc(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("acc", Box::new(AccHelper));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case("{{#> layout}}x{{@index}}{{/layout}}", "<[x0][x1]>")]
+    #[case(
+        "{{#> layout}}{{#> nested}}{{@index}}{{/nested}}{{/layout}}",
+        "<[(0)(1)][(0)(1)]>"
+    )]
+    #[case(
+        "{{#repeat 2}}{{#> layout}}{{@index}}{{/layout}}{{/repeat}}",
+        "<[0][1]><[0][1]>"
+    )]
+    #[case(
+        "{{#*inline \"b\"}}B{{/inline}}{{#> layout}}{{> b}}{{/layout}}",
+        "<[B][B]>"
+    )]
+    #[case("{{#> layout}}{{v}}{{/layout}}", "<[&lt;q&gt;][&lt;q&gt;]>")]
+    #[case("{{#> escape}}<{{v}}>{{/escape}}", "<<q>><<q>>")]
+    #[case("{{#> skip}}{{v}}{{/skip}}", "&lt;q&gt;&lt;q&gt;")]
+    #[case("{{#> chomp}}\nz\n{{/chomp}}", "  zz")]
+    fn partial_block(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let partials = [
+            ("layout", "<{{#repeat 2}}[{{> @partial-block}}]{{/repeat}}>"),
+            ("nested", "{{#repeat 2}}({{> @partial-block}}){{/repeat}}"),
+            (
+                "escape",
+                "{{#repeat 2 escape=\"none\"}}{{> @partial-block}}{{/repeat}}",
+            ),
+            (
+                "skip",
+                "{{#repeat 2 on_error=\"skip\"}}{{> @partial-block}}{{/repeat}}",
+            ),
+            (
+                "chomp",
+                "{{#repeat 2 chomp=true indent=2}}{{> @partial-block}}{{/repeat}}",
+            ),
+        ];
+        for (name, partial) in partials {
+            reg.register_partial(name, partial).unwrap();
+        }
+
+        let data = json!({"v": "<q>"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{*repeat 3 name=\"rows\"}}{{#each rows}}{{this}}{{/each}}", "012")]
+    #[case(
+        "{{*repeat 2 name=\"rows\" fill=name}}{{#each rows}}{{this}};{{/each}}{{name}}",
+        "foo;foo;foo"
+    )]
+    #[case(
+        "{{*repeat 0 name=\"rows\"}}{{#each rows}}x{{else}}none{{/each}}",
+        "none"
+    )]
+    #[case(
+        "{{*repeat 2 name=\"a\"}}{{*repeat 1 name=\"b\"}}{{#each a}}a{{/each}}{{#each b}}b{{/each}}{{lookup a 1}}",
+        "aab1"
+    )]
+    #[case(
+        "{{*repeat \"3\" name=\"rows\"}}{{#each rows}}{{this}}{{/each}}",
+        "012"
+    )]
+    #[case("{{*repeat 2.0 name=\"rows\"}}{{#each rows}}{{this}}{{/each}}", "01")]
+    #[case(
+        "{{#with obj}}{{*repeat 2 name=\"x\"}}{{#each x}}{{this}}{{/each}}{{y}}{{/with}}{{#each x}}!{{/each}}",
+        "01z"
+    )]
+    #[case(
+        "{{#each items}}{{*repeat n name=\"x\"}}[{{#each x}}{{this}}{{/each}}]{{/each}}",
+        "[0][01]"
+    )]
+    #[case(
+        "{{#repeat 2}}{{*repeat 2 name=\"x\"}}{{#each x}}{{this}}{{/each}}{{/repeat}}",
+        "0101"
+    )]
+    fn decorator(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_decorator("repeat", Box::new(RepeatDecorator::default()));
+        let data = json!({"name": "foo", "obj": {"y": "z"}, "items": [{"n": 1}, {"n": 2}]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn decorator_without_data() {
+        let mut reg = Handlebars::new();
+        reg.register_decorator("repeat", Box::new(RepeatDecorator::default()));
+        let template = "{{*repeat 2 name=\"rows\"}}{{#each rows}}{{this}}{{/each}}";
+        assert_eq!(reg.render_template(template, &()).unwrap(), "01");
+    }
+
+    #[rstest]
+    #[case(
+        "{{*repeat name=\"rows\"}}",
+        RenderErrorReason::ParamNotFoundForIndex("repeat", 0).into()
+    )]
+    #[case(
+        "{{*repeat -1 name=\"rows\"}}",
+        Error::NegativeCount(-1.0).into()
+    )]
+    #[case(
+        "{{*repeat 2}}",
+        RenderErrorReason::ParamNotFoundForName("repeat", "name".to_string()).into()
+    )]
+    #[case(
+        "{{*repeat 2 name=1}}",
+        RenderErrorReason::HashTypeMismatchForName("repeat", "name".to_string(), "string".to_string()).into()
+    )]
+    #[case(
+        "{{*repeat 11 name=\"rows\"}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    fn wrong_decorator(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_decorator(
+            "repeat",
+            Box::new(RepeatDecorator::default().with_max_count(10)),
+        );
+        assert_fails(reg.render_template(template, &json!({})), expected);
+        assert_fails(
+            reg.render_template("{{*repeat 1 name=\"x\"}}", &json!([1])),
+            RenderErrorReason::InvalidJsonPath("x".to_string()).into(),
+        );
+    }
+
+    #[rstest]
+    #[case("{{#range 1 10 2}}{{this}}{{/range}}", "13579")]
+    #[case(
+        "{{#range 1 4}}{{this}}:{{@index}}:{{@first}}:{{@last}} {{/range}}",
+        "1:1:true:false 2:2:false:false 3:3:false:true "
+    )]
+    #[case("{{#range 3}}{{this}}{{/range}}", "012")]
+    #[case("{{#range 0 10 5 inclusive=true}}{{this}} {{/range}}", "0 5 10 ")]
+    #[case("{{#range 1 4 reverse=true}}{{this}}{{/range}}", "321")]
+    #[case("{{#range 4 1}}x{{else}}empty{{/range}}", "empty")]
+    #[case("{{#range 0 2 as |i|}}{{i}}{{this}}{{/range}}", "0011")]
+    #[case("{{#range 0 2 this=\"name\"}}{{this}}{{/range}}", "foofoo")]
+    #[case("{{#repeat 1 4 2}}{{this.name}}{{/repeat}}", "foofoofoo")]
+    fn range_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("range", Box::new(RangeHelper::default()));
+        let data = json!({"name": "foo"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#range 1 10 0}}x{{/range}}",
+        RenderErrorReason::HashTypeMismatchForName("repeat", "step".to_string(), "non-zero u64".to_string()).into()
+    )]
+    #[case(
+        "{{#range 1 10 \"x\"}}x{{/range}}",
+        RenderErrorReason::ParamTypeMismatchForName("repeat", "2".to_string(), "u64".to_string()).into()
+    )]
+    #[case(
+        "{{#range 0 11}}x{{/range}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    fn wrong_range(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        let helper = RangeHelper::from(RepeatHelper::default().with_max_count(10));
+        reg.register_helper("range", Box::new(helper));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case("{{#each (range 0 5)}}{{this}}{{/each}}", "01234")]
+    #[case("{{#each (range 1 10 4)}}{{this}};{{/each}}", "1;5;9;")]
+    #[case("{{#each (range 3)}}{{@index}}{{/each}}", "012")]
+    #[case("{{#each (range 0 4 reverse=true skip=1)}}{{this}}{{/each}}", "210")]
+    #[case("{{#each (range 5 1)}}x{{else}}empty{{/each}}", "empty")]
+    #[case("{{lookup (range 10 20 5) 1}}", "15")]
+    #[case("{{#each (range 0 3 inclusive=true)}}{{this}}{{/each}}", "0123")]
+    fn range_subexpression(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("range", Box::new(RangeHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#each (repeat 3)}}{{this}}{{/each}}", "012")]
+    #[case("{{#each (repeat 3 start=1 step=2)}}{{this}};{{/each}}", "1;3;5;")]
+    #[case("{{#each (repeat 2 5)}}{{this}}{{/each}}", "234")]
+    #[case("{{#each (repeat 3 reverse=true)}}{{this}}{{/each}}", "210")]
+    #[case("{{#each (repeat 5 skip=1 limit=2)}}{{this}}{{/each}}", "12")]
+    #[case("{{#each (repeat list)}}{{this}}{{/each}}", "01")]
+    #[case("{{#each (repeat 0)}}x{{else}}empty{{/each}}", "empty")]
+    #[case("{{lookup (repeat 5 step=10) 2}}", "20")]
+    fn repeat_subexpression(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        let data = json!({"list": ["a", "b"]});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[test]
+    fn repeat_subexpression_limits() {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "repeat",
+            Box::new(RepeatHelper::default().with_max_count(3)),
+        );
+        assert_fails(
+            reg.render_template("{{#each (repeat 4)}}{{/each}}", &()),
+            Error::TooManyRepetitions { count: 4, max: 3 }.into(),
+        );
+        assert_fails(
+            reg.render_template("{{repeat 4}}", &()),
+            Error::TooManyRepetitions { count: 4, max: 3 }.into(),
+        );
+    }
+
+    #[rstest]
+    #[case("{{#times 3}}{{@index}}{{/times}}", "012")]
+    #[case("{{#times 0}}x{{else}}none{{/times}}", "none")]
+    #[case(
+        "{{#times 2 separator=\",\"}}{{#repeat 2}}{{@index}}{{/repeat}}{{/times}}",
+        "01,01"
+    )]
+    #[case("{{#each (times 2)}}{{this}}{{/each}}", "01")]
+    fn times_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("repeat", Box::new(RepeatHelper::default()));
+        reg.register_helper("times", Box::new(TimesHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case("{{#seq 3}}{{this}}{{/seq}}", "123")]
+    #[case("{{#seq 2 4}}{{this}}{{/seq}}", "234")]
+    #[case("{{#seq 1 2 7}}{{this}}{{/seq}}", "1357")]
+    #[case("{{#seq 1 2 8}}{{this}}{{/seq}}", "1357")]
+    #[case("{{#seq 5 -2 0}}{{this}}{{/seq}}", "531")]
+    #[case("{{#seq -2 0}}{{this}};{{/seq}}", "-2;-1;0;")]
+    #[case("{{#seq 4 4}}{{this}}{{/seq}}", "4")]
+    #[case("{{#seq 5 1}}x{{else}}empty{{/seq}}", "empty")]
+    #[case("{{#seq 1 -1 5}}x{{else}}empty{{/seq}}", "empty")]
+    #[case("{{#seq 0}}x{{else}}empty{{/seq}}", "empty")]
+    #[case("{{#seq 9 11 format=\"%02d\"}}{{this}} {{/seq}}", "09 10 11 ")]
+    #[case("{{#seq -1 1 format=\"%03d\"}}{{this}} {{/seq}}", "-01 000 001 ")]
+    #[case("{{#seq 3 format=\"f%d\"}}{{@index}}{{@value}}{{this}}{{#if @first}}F{{/if}}{{#if @last}}L{{/if}} {{/seq}}", "01f1F 12f2 23f3L ")]
+    #[case("{{#seq 2 as |v|}}{{v}}{{/seq}}", "12")]
+    #[case("{{#seq 1 1 2}}{{@root.name}}{{/seq}}", "foofoo")]
+    #[case("{{#seq 0 0.25 1}}{{this}} {{/seq}}", "0.00 0.25 0.50 0.75 1.00 ")]
+    #[case(
+        "{{#seq 1 -0.5 0 as |v|}}{{this}}={{v}} {{/seq}}",
+        "1.0=1.0 0.5=0.5 0.0=0.0 "
+    )]
+    #[case("{{#seq -0.5 1}}{{this}} {{/seq}}", "-0.5 0.5 ")]
+    #[case("{{#seq 0.1 0.1 0.3}}{{@value}};{{/seq}}", "0.1;0.2;0.3;")]
+    #[case("{{#seq 1.5}}{{this}}{{/seq}}", "1.0")]
+    #[case("{{#seq 3}}{{@roman}}{{#sep}},{{/sep}}{{/seq}}", "i,ii,iii")]
+    #[case(
+        "{{#seq 5}}{{this}}{{#if (eq @index 1)}}{{break}}{{/if}}{{/seq}}",
+        "12"
+    )]
+    fn seq(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("seq", Box::new(SeqHelper::default()));
+        reg.register_helper("sep", Box::new(SepHelper));
+        reg.register_helper("break", Box::new(BreakHelper));
+        let data = json!({"name": "foo"});
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#seq}}x{{/seq}}",
+        RenderErrorReason::ParamNotFoundForIndex("seq", 0).into()
+    )]
+    #[case(
+        "{{#seq 1 0 5}}x{{/seq}}",
+        RenderErrorReason::ParamTypeMismatchForName("seq", "1".to_string(), "non-zero number".to_string()).into()
+    )]
+    #[case(
+        "{{#seq 0.0000000001}}x{{/seq}}",
+        RenderErrorReason::ParamTypeMismatchForName("seq", "0".to_string(), "number".to_string()).into()
+    )]
+    #[case(
+        "{{#seq 1e300}}x{{/seq}}",
+        RenderErrorReason::ParamTypeMismatchForName("seq", "0".to_string(), "number".to_string()).into()
+    )]
+    #[case(
+        "{{#seq 0.5 format=\"%d\"}}x{{/seq}}",
+        RenderErrorReason::HashTypeMismatchForName("seq", "format".to_string(), "printf-style format of integers".to_string()).into()
+    )]
+    #[case(
+        "{{#seq \"a\"}}x{{/seq}}",
+        RenderErrorReason::ParamTypeMismatchForName("seq", "0".to_string(), "number".to_string()).into()
+    )]
+    #[case(
+        "{{#seq 3 format=\"%s\"}}x{{/seq}}",
+        RenderErrorReason::HashTypeMismatchForName("seq", "format".to_string(), "printf-style format of integers".to_string()).into()
+    )]
+    #[case(
+        "{{#seq 11}}x{{/seq}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    #[case(
+        "{{seq 3}}",
+        RenderErrorReason::BlockContentRequired.into()
+    )]
+    fn wrong_seq(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("seq", Box::new(SeqHelper::default().with_max_count(10)));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case("{{#countdown 5}}{{this}}{{/countdown}}", "54321")]
+    #[case(
+        "{{#countdown 1}}{{this}}{{@first}}{{@last}}{{/countdown}}",
+        "1truetrue"
+    )]
+    #[case("{{#countdown 0}}x{{else}}none{{/countdown}}", "none")]
+    #[case("{{#countdown 3}}{{@index}}{{@value}} {{/countdown}}", "03 12 21 ")]
+    #[case(
+        "{{#countdown 10 format=\"T-%02d\"}}{{#if @last}}{{this}}{{/if}}{{/countdown}}",
+        "T-01"
+    )]
+    fn countdown(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("countdown", Box::new(CountdownHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[test]
+    fn wrong_countdown() {
+        let mut reg = Handlebars::new();
+        let helper = CountdownHelper::from(SeqHelper::default().with_max_count(3));
+        reg.register_helper("countdown", Box::new(helper));
+        assert_fails(
+            reg.render_template("{{#countdown 4}}{{/countdown}}", &()),
+            Error::TooManyRepetitions { count: 4, max: 3 }.into(),
+        );
+        assert_fails(
+            reg.render_template("{{#countdown}}{{/countdown}}", &()),
+            RenderErrorReason::ParamNotFoundForIndex("seq", 0).into(),
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "{{#while \"lookup items @index\" max=10}}{{lookup items @index}}{{/while}}",
+        "abc"
+    )]
+    #[case(
+        "{{#while \"lookup items @index\" max=10}}{{@index}}{{@first}} {{/while}}",
+        "0true 1false 2false "
+    )]
+    #[case("{{#while \"lookup items @index\" max=2}}{{@index}}{{/while}}", "01")]
+    #[case("{{#while true max=3 as |i|}}{{i}}{{/while}}", "012")]
+    #[case("{{#while missing max=3}}x{{else}}none{{/while}}", "none")]
+    #[case("{{#while true max=0}}x{{else}}none{{/while}}", "none")]
+    #[case(
+        "{{#while true max=9}}{{#if (eq @index 3)}}{{break}}{{/if}}{{@index}}{{/while}}",
+        "012"
+    )]
+    #[case(
+        "{{#while true max=4}}{{#if (eq @index 1)}}{{continue}}{{/if}}{{@index}}{{/while}}",
+        "023"
+    )]
+    #[case(
+        "{{#while \"lookup items @index\" max=9}}{{acc \"n\" 2}}{{/while}}{{acc \"n\"}}",
+        "6"
+    )]
+    #[case(
+        "{{#each items}}{{#while \"lt @index 2\" max=9}}{{../this}}{{/while}}{{/each}}",
+        "aabbcc"
+    )]
+    #[case(
+        "{{#while \"lookup items @index\" max=9}}{{lookup items @index}}{{#sep}}, {{/sep}}{{/while}}",
+        "a, b, c"
+    )]
+    #[case(
+        "{{#while true max=3}}{{@index}}{{#if @last}}!{{/if}}{{/while}}",
+        "012!"
+    )]
+    #[case("{{#while true max=\"2\"}}x{{/while}}", "xx")]
+    #[case("{{#while true max=2.0}}x{{/while}}", "xx")]
+    fn while_helper(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("while", Box::new(WhileHelper::default()));
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_helper("continue", Box::new(ContinueHelper));
+        reg.register_helper("acc", Box::new(AccHelper));
+        reg.register_helper("sep", Box::new(SepHelper));
+        let data = serde_json::json!({ "items": ["a", "b", "c"] });
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+
+        reg.register_template_string("t", template).unwrap();
+        assert_eq!(reg.render("t", &data).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#while true}}x{{/while}}",
+        RenderErrorReason::ParamNotFoundForName("while", "max".to_string()).into()
+    )]
+    #[case(
+        "{{#while true max=\"x\"}}x{{/while}}",
+        RenderErrorReason::HashTypeMismatchForName("while", "max".to_string(), "u64".to_string()).into()
+    )]
+    #[case(
+        "{{#while true max=11}}x{{/while}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    #[case(
+        "{{#while max=3}}x{{/while}}",
+        RenderErrorReason::ParamNotFoundForIndex("while", 0).into()
+    )]
+    fn wrong_while(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("while", Box::new(WhileHelper::default().with_max_count(10)));
+        reg.register_template_string("t", template).unwrap();
+        assert_fails(reg.render("t", &()), expected);
+    }
+
+    fn unfolding() -> Handlebars<'static> {
+        handlebars_helper!(mul: |a: Option<i64>, b: i64| a.unwrap_or_default() * b);
+        handlebars_helper!(add: |a: Option<i64>, b: i64| a.unwrap_or_default() + b);
+
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "unfold",
+            Box::new(UnfoldHelper::default().with_max_count(10)),
+        );
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_helper("acc", Box::new(AccHelper));
+        reg.register_helper("sep", Box::new(SepHelper));
+        reg.register_helper("mul", Box::new(mul));
+        reg.register_helper("add", Box::new(add));
+        reg
+    }
+
+    #[rstest]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\" count=8}}{{this}} {{/unfold}}",
+        "1 2 4 8 16 32 64 128 "
+    )]
+    #[case("{{#unfold seed=3 next=\"add @value -1\" count=3}}{{@index}}{{@value}}{{@first}}{{@last}} {{/unfold}}", "03truefalse 12falsefalse 21falsetrue ")]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 3\" count=3 as |v|}}{{v}}{{/unfold}}",
+        "139"
+    )]
+    #[case("{{#unfold seed=\"x\" next=@value count=2}}{{this}}{{/unfold}}", "xx")]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\" count=0}}x{{else}}none{{/unfold}}",
+        "none"
+    )]
+    #[case("{{#unfold seed=1 next=\"mul @value 2\" count=9}}{{#if (gt this 4)}}{{break}}{{/if}}{{this}}{{/unfold}}", "124")]
+    #[case(
+        "{{#unfold seed=1 next=\"add @value 1\" count=4}}{{acc \"n\" this}}{{/unfold}}{{acc \"n\"}}",
+        "10"
+    )]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\" count=3}}{{this}}{{#sep}}, {{/sep}}{{/unfold}}",
+        "1, 2, 4"
+    )]
+    #[case(
+        "{{#unfold seed=1 next=\"add @value 1\" count=3}}{{@rindex}}{{@parity}} {{/unfold}}",
+        "2even 1odd 0even "
+    )]
+    #[case("{{#unfold seed=1 next=\"mul @value 2\" count=2}}{{#unfold seed=this next=\"add @value 1\" count=2}}{{this}}{{/unfold}};{{/unfold}}", "12;23;")]
+    fn unfold(#[case] template: &str, #[case] output: &str) {
+        let mut reg = unfolding();
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+
+        reg.register_template_string("t", template).unwrap();
+        assert_eq!(reg.render("t", &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#unfold next=\"mul @value 2\" count=2}}x{{/unfold}}",
+        RenderErrorReason::ParamNotFoundForName("unfold", "seed".to_string()).into()
+    )]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\"}}x{{/unfold}}",
+        RenderErrorReason::ParamNotFoundForName("unfold", "count".to_string()).into()
+    )]
+    #[case(
+        "{{#unfold seed=1 count=2}}x{{/unfold}}",
+        RenderErrorReason::ParamNotFoundForName("unfold", "next".to_string()).into()
+    )]
+    #[case(
+        "{{#unfold seed=1 next=2 count=2}}x{{/unfold}}",
+        RenderErrorReason::HashTypeMismatchForName("unfold", "next".to_string(), "path or expression".to_string()).into()
+    )]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\" count=-1}}x{{/unfold}}",
+        RenderErrorReason::HashTypeMismatchForName("unfold", "count".to_string(), "u64".to_string()).into()
+    )]
+    #[case(
+        "{{#unfold seed=1 next=\"mul @value 2\" count=11}}x{{/unfold}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    fn wrong_unfold(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = unfolding();
+        reg.register_template_string("t", template).unwrap();
+        assert_fails(reg.render("t", &()), expected);
+    }
+
+    #[rstest]
+    #[case("{{#chars \"a\" \"e\"}}{{this}}{{/chars}}", "abcde")]
+    #[case("{{#chars \"e\" \"a\"}}{{@char}}{{/chars}}", "edcba")]
+    #[case(
+        "{{#chars \"x\" \"x\"}}{{@index}}{{@char}}{{@first}}{{@last}}{{/chars}}",
+        "0xtruetrue"
+    )]
+    #[case("{{#chars \"α\" \"γ\" as |c|}}{{c}}{{@index}}{{/chars}}", "α0β1γ2")]
+    #[case("{{#chars \"\u{d7ff}\" \"\u{e000}\"}}{{@index}}{{/chars}}", "01")]
+    #[case("{{#chars \"\u{e000}\" \"\u{d7ff}\"}}{{@last}}{{/chars}}", "falsetrue")]
+    #[case("{{#alphabet}}{{this}}{{/alphabet}}", "abcdefghijklmnopqrstuvwxyz")]
+    #[case(
+        "{{#alphabet upper=true}}{{#if @last}}{{@index}}{{this}}{{/if}}{{/alphabet}}",
+        "25Z"
+    )]
+    fn chars(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("chars", Box::new(CharsHelper::default()));
+        reg.register_helper("alphabet", Box::new(AlphabetHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#chars \"a\"}}x{{/chars}}",
+        RenderErrorReason::ParamNotFoundForIndex("chars", 1).into()
+    )]
+    #[case(
+        "{{#chars \"a\" \"\"}}x{{/chars}}",
+        RenderErrorReason::ParamTypeMismatchForName("chars", "1".to_string(), "char".to_string()).into()
+    )]
+    #[case(
+        "{{#chars \"a\" \"zz\"}}x{{/chars}}",
+        RenderErrorReason::ParamTypeMismatchForName("chars", "1".to_string(), "char".to_string()).into()
+    )]
+    #[case(
+        "{{#chars 1 2}}x{{/chars}}",
+        RenderErrorReason::ParamTypeMismatchForName("chars", "0".to_string(), "char".to_string()).into()
+    )]
+    #[case(
+        "{{#chars \"a\" \"z\"}}x{{/chars}}",
+        Error::TooManyRepetitions { count: 26, max: 10 }.into()
+    )]
+    #[case(
+        "{{chars \"a\" \"c\"}}",
+        RenderErrorReason::BlockContentRequired.into()
+    )]
+    fn wrong_chars(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("chars", Box::new(CharsHelper::default().with_max_count(10)));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#dates from=\"2024-01-30\" to=\"2024-02-02\"}}{{this}} {{/dates}}",
+        "2024-01-30 2024-01-31 2024-02-01 2024-02-02 "
+    )]
+    #[case("{{#dates from=\"2024-01-01\" to=\"2024-01-31\" step=\"1w\"}}{{@index}}{{@weekday}}{{#if @last}}{{@date}}{{/if}} {{/dates}}", "0Monday 1Monday 2Monday 3Monday 4Monday2024-01-29 ")]
+    #[case(
+        "{{#dates from=\"2024-01-31\" to=\"2024-06-30\" step=\"2m\" as |d|}}{{d}} {{/dates}}",
+        "2024-01-31 2024-03-31 2024-05-31 "
+    )]
+    #[case("{{#dates from=\"2020-02-29\" to=\"2024-03-01\" step=\"2y\" format=\"%Y/%j\"}}{{this}} {{/dates}}", "2020/060 2022/059 2024/060 ")]
+    #[case(
+        "{{#dates from=\"2024-01-01\" to=\"2024-01-01\"}}{{@first}}{{@last}}{{/dates}}",
+        "truetrue"
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-02\" to=\"2024-01-01\"}}x{{else}}none{{/dates}}",
+        "none"
+    )]
+    fn dates(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("dates", Box::new(DatesHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#dates to=\"2024-01-01\"}}x{{/dates}}",
+        RenderErrorReason::ParamNotFoundForName("dates", "from".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-01\"}}x{{/dates}}",
+        RenderErrorReason::ParamNotFoundForName("dates", "to".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-02-30\" to=\"2024-03-01\"}}x{{/dates}}",
+        RenderErrorReason::HashTypeMismatchForName("dates", "from".to_string(), "date".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-01\" to=\"2024-01-02\" step=\"0d\"}}x{{/dates}}",
+        RenderErrorReason::HashTypeMismatchForName("dates", "step".to_string(), "step such as 1d".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-01\" to=\"2024-01-02\" step=\"1h\"}}x{{/dates}}",
+        RenderErrorReason::HashTypeMismatchForName("dates", "step".to_string(), "step such as 1d".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-01\" to=\"2024-01-02\" format=\"%Q\"}}x{{/dates}}",
+        RenderErrorReason::HashTypeMismatchForName("dates", "format".to_string(), "strftime-style format".to_string()).into()
+    )]
+    #[case(
+        "{{#dates from=\"2024-01-01\" to=\"2024-12-31\"}}x{{/dates}}",
+        Error::TooManyRepetitions { count: 366, max: 100 }.into()
+    )]
+    fn wrong_dates(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "dates",
+            Box::new(DatesHelper::default().with_max_count(100)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#months from=\"2024-11\" to=\"2025-02\"}}{{this}} {{/months}}",
+        "2024-11 2024-12 2025-01 2025-02 "
+    )]
+    #[case("{{#months from=\"2024-01\" to=\"2024-03\" as |m|}}{{m}}:{{@year}}/{{@month}}:{{@month_name}} {{/months}}", "2024-01:2024/1:January 2024-02:2024/2:February 2024-03:2024/3:March ")]
+    #[case(
+        "{{#months from=\"2024-05\" to=\"2024-05\"}}{{@index}}{{@first}}{{@last}}{{/months}}",
+        "0truetrue"
+    )]
+    #[case(
+        "{{#months from=\"2024-05\" to=\"2024-04\"}}x{{else}}none{{/months}}",
+        "none"
+    )]
+    fn months(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("months", Box::new(MonthsHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(all(feature = "time", feature = "i18n"))]
+    #[rstest]
+    #[case(
+        "{{#months from=\"2024-02\" to=\"2024-03\" locale=\"de\"}}{{@month_name}} {{/months}}",
+        "Februar März "
+    )]
+    #[case(
+        "{{#months from=\"2024-08\" to=\"2024-08\" locale=\"fr_CA\"}}{{@month_name}}{{/months}}",
+        "août"
+    )]
+    fn months_locale(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("months", Box::new(MonthsHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#months to=\"2024-01\"}}x{{/months}}",
+        RenderErrorReason::ParamNotFoundForName("months", "from".to_string()).into()
+    )]
+    #[case(
+        "{{#months from=\"2024-01\"}}x{{/months}}",
+        RenderErrorReason::ParamNotFoundForName("months", "to".to_string()).into()
+    )]
+    #[case(
+        "{{#months from=\"2024-13\" to=\"2025-01\"}}x{{/months}}",
+        RenderErrorReason::HashTypeMismatchForName("months", "from".to_string(), "month".to_string()).into()
+    )]
+    #[case(
+        "{{#months from=\"2024-01-01\" to=\"2025-01\"}}x{{/months}}",
+        RenderErrorReason::HashTypeMismatchForName("months", "from".to_string(), "month".to_string()).into()
+    )]
+    #[case(
+        "{{#months from=\"2024-01\" to=\"2025-01\" locale=\"xx\"}}x{{/months}}",
+        RenderErrorReason::HashTypeMismatchForName("months", "locale".to_string(), "known locale".to_string()).into()
+    )]
+    #[case(
+        "{{#months from=\"2000-01\" to=\"2024-01\"}}x{{/months}}",
+        Error::TooManyRepetitions { count: 289, max: 100 }.into()
+    )]
+    fn wrong_months(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "months",
+            Box::new(MonthsHelper::default().with_max_count(100)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case("{{#business_days from=\"2024-01-05\" to=\"2024-01-09\"}}{{@index}}{{@weekday}} {{/business_days}}", "0Friday 1Monday 2Tuesday ")]
+    #[case("{{#business_days from=\"2024-01-01\" to=\"2024-01-05\" holidays=holidays}}{{this}} {{/business_days}}", "2024-01-02 2024-01-03 2024-01-05 ")]
+    #[case("{{#business_days from=\"2024-01-01\" to=\"2024-01-31\" format=\"%d\"}}{{#if @last}}{{@index}}:{{this}}{{/if}}{{/business_days}}", "22:31")]
+    #[case("{{#business_days from=\"2024-01-06\" to=\"2024-01-07\"}}x{{else}}weekend{{/business_days}}", "weekend")]
+    #[case("{{#business_days from=\"2024-01-08\" to=\"2024-01-08\" as |d|}}{{d}}{{@first}}{{@last}}{{/business_days}}", "2024-01-08truetrue")]
+    fn business_days(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("business_days", Box::new(BusinessDaysHelper::default()));
+        let data = serde_json::json!({ "holidays": ["2024-01-01", "2024-01-04"] });
+        assert_eq!(reg.render_template(template, &data).unwrap(), output);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#business_days from=\"2024-01-01\"}}x{{/business_days}}",
+        RenderErrorReason::ParamNotFoundForName("business_days", "to".to_string()).into()
+    )]
+    #[case(
+        "{{#business_days from=\"2024-01-01\" to=\"2024-01-05\" holidays=\"2024-01-02\"}}x{{/business_days}}",
+        RenderErrorReason::HashTypeMismatchForName("business_days", "holidays".to_string(), "array of dates".to_string()).into()
+    )]
+    #[case(
+        "{{#business_days from=\"2024-01-01\" to=\"2024-01-05\" holidays=bad}}x{{/business_days}}",
+        RenderErrorReason::HashTypeMismatchForName("business_days", "holidays".to_string(), "array of dates".to_string()).into()
+    )]
+    #[case(
+        "{{#business_days from=\"2024-01-01\" to=\"2024-01-31\"}}x{{/business_days}}",
+        Error::TooManyRepetitions { count: 23, max: 20 }.into()
+    )]
+    fn wrong_business_days(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        let helper = BusinessDaysHelper::from(DatesHelper::default().with_max_count(20));
+        reg.register_helper("business_days", Box::new(helper));
+        let data = serde_json::json!({ "bad": ["2024-01-02", "soon"] });
+        assert_fails(reg.render_template(template, &data), expected);
+    }
+
+    #[rstest]
+    #[case("{{#hours from=9 to=12}}{{this}} {{/hours}}", "09:00 10:00 11:00 ")]
+    #[case(
+        "{{#hours from=9 to=12 inclusive=true}}{{@hour}} {{/hours}}",
+        "09:00 10:00 11:00 12:00 "
+    )]
+    #[case(
+        "{{#hours from=9.5 to=11 step=0.5}}{{@index}}{{@value}} {{/hours}}",
+        "09.5 110.0 210.5 "
+    )]
+    #[case("{{#hours from=22 to=24 step=0.75 inclusive=true as |t|}}{{t}}{{#if @last}}!{{/if}} {{/hours}}", "22:00 22:45 23:30! ")]
+    #[case(
+        "{{#hours from=0 to=1 step=0.25}}{{@even}}{{@count}}{{sep}}{{/hours}}",
+        "true4false4true4false4"
+    )]
+    #[case("{{#hours from=8 to=8}}x{{else}}closed{{/hours}}", "closed")]
+    #[case("{{#hours from=23 to=24 inclusive=true}}{{this}} {{/hours}}", "23:00 ")]
+    #[case(
+        "{{#hours from=24 to=24 inclusive=true}}x{{else}}closed{{/hours}}",
+        "closed"
+    )]
+    #[case("{{#hours from=8 to=12}}{{#if (eq @index 2)}}{{break}}{{/if}}{{@hour}}{{#sep}},{{/sep}}{{/hours}}", "08:00,09:00,")]
+    fn hours(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("hours", Box::new(HoursHelper::default()));
+        reg.register_helper("break", Box::new(BreakHelper));
+        reg.register_helper("sep", Box::new(SepHelper));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#hours to=12}}x{{/hours}}",
+        RenderErrorReason::ParamNotFoundForName("hours", "from".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=9}}x{{/hours}}",
+        RenderErrorReason::ParamNotFoundForName("hours", "to".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=9 to=25}}x{{/hours}}",
+        RenderErrorReason::HashTypeMismatchForName("hours", "to".to_string(), "hours between 0 and 24".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=-1 to=2}}x{{/hours}}",
+        RenderErrorReason::HashTypeMismatchForName("hours", "from".to_string(), "hours between 0 and 24".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=\"9\" to=12}}x{{/hours}}",
+        RenderErrorReason::HashTypeMismatchForName("hours", "from".to_string(), "hours between 0 and 24".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=9 to=12 step=0}}x{{/hours}}",
+        RenderErrorReason::HashTypeMismatchForName("hours", "step".to_string(), "at least a minute".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=9 to=12 inclusive=\"yes\"}}x{{/hours}}",
+        RenderErrorReason::HashTypeMismatchForName("hours", "inclusive".to_string(), "bool".to_string()).into()
+    )]
+    #[case(
+        "{{#hours from=0 to=24 step=0.25}}x{{/hours}}",
+        Error::TooManyRepetitions { count: 96, max: 48 }.into()
+    )]
+    fn wrong_hours(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("hours", Box::new(HoursHelper::default().with_max_count(48)));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case("{{#weeks year=2025}}{{#if @last}}{{@index}} {{this}} {{@week}} {{@year}} {{@start_date}} {{@end_date}}{{/if}}{{/weeks}}", "51 2025-W52 52 2025 2025-12-22 2025-12-28")]
+    #[case(
+        "{{#weeks year=2020}}{{#if @last}}{{this}} {{@end_date}}{{/if}}{{/weeks}}",
+        "2020-W53 2021-01-03"
+    )]
+    #[case("{{#weeks from=\"2024-12-25\" to=\"2025-01-06\" format=\"%d.%m\"}}{{this}}:{{@start_date}}-{{@end_date}} {{/weeks}}", "2024-W52:23.12-29.12 2025-W01:30.12-05.01 2025-W02:06.01-12.01 ")]
+    #[case(
+        "{{#weeks from=\"2025-03-03\" to=\"2025-03-09\" as |w|}}{{w}}{{@first}}{{@last}}{{/weeks}}",
+        "2025-W10truetrue"
+    )]
+    #[case(
+        "{{#weeks from=\"2025-03-09\" to=\"2025-03-03\"}}x{{else}}none{{/weeks}}",
+        "none"
+    )]
+    fn weeks(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("weeks", Box::new(WeeksHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#weeks}}x{{/weeks}}",
+        RenderErrorReason::ParamNotFoundForName("weeks", "from".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks year=\"2025\"}}x{{/weeks}}",
+        RenderErrorReason::HashTypeMismatchForName("weeks", "year".to_string(), "year".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks year=0}}x{{/weeks}}",
+        RenderErrorReason::HashTypeMismatchForName("weeks", "year".to_string(), "year".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks from=\"2025-01-01\"}}x{{/weeks}}",
+        RenderErrorReason::ParamNotFoundForName("weeks", "to".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks year=2025 format=\"%Z\"}}x{{/weeks}}",
+        RenderErrorReason::HashTypeMismatchForName("weeks", "format".to_string(), "strftime-style format".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks year=2025 from=\"2025-03-01\"}}x{{/weeks}}",
+        RenderErrorReason::Other("weeks helper given both year and from or to".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks year=2025 to=\"2025-03-31\"}}x{{/weeks}}",
+        RenderErrorReason::Other("weeks helper given both year and from or to".to_string()).into()
+    )]
+    #[case(
+        "{{#weeks from=\"2020-01-01\" to=\"2025-01-01\"}}x{{/weeks}}",
+        Error::TooManyRepetitions { count: 262, max: 60 }.into()
+    )]
+    fn wrong_weeks(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("weeks", Box::new(WeeksHelper::default().with_max_count(60)));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case("{{#calendar year=2025 month=6}}{{#if @first}}{{this}} {{@weekday}}{{/if}}{{#if @last}} {{this}} {{@index}} {{@week}}{{/if}}{{/calendar}}", "2025-05-26 Monday 2025-07-06 41 5")]
+    #[case("{{#calendar year=2026 month=2}}{{#if @week_start}}[{{/if}}{{@day}}{{#if @week_end}}]{{/if}}{{/calendar}}", "[2627282930311][2345678][9101112131415][16171819202122][2324252627281]")]
+    #[case("{{#calendar year=2026 month=2 week_start=\"sunday\"}}{{#if @in_month}}{{#if @first}}{{@weekday}}{{/if}}{{/if}}{{#if @week_start}}{{@weekday}};{{/if}}{{/calendar}}", "SundaySunday;Sunday;Sunday;Sunday;")]
+    #[case("{{#calendar year=2024 month=2 as |d|}}{{#if @in_month}}{{#if @last}}{{d}}{{/if}}{{/if}}{{/calendar}}", "")]
+    #[case("{{#calendar year=2024 month=3}}{{#if @in_month}}{{#if (eq @day 31)}}{{this}} {{@weekday}}{{/if}}{{/if}}{{/calendar}}", "2024-03-31 Sunday")]
+    fn calendar(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("calendar", Box::new(CalendarHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case(
+        "{{#calendar month=6}}x{{/calendar}}",
+        RenderErrorReason::ParamNotFoundForName("calendar", "year".to_string()).into()
+    )]
+    #[case(
+        "{{#calendar year=2025}}x{{/calendar}}",
+        RenderErrorReason::ParamNotFoundForName("calendar", "month".to_string()).into()
+    )]
+    #[case(
+        "{{#calendar year=2025 month=13}}x{{/calendar}}",
+        RenderErrorReason::HashTypeMismatchForName("calendar", "month".to_string(), "month".to_string()).into()
+    )]
+    #[case(
+        "{{#calendar year=2025 month=\"6\"}}x{{/calendar}}",
+        RenderErrorReason::HashTypeMismatchForName("calendar", "month".to_string(), "month".to_string()).into()
+    )]
+    #[case(
+        "{{#calendar year=2025 month=6 week_start=\"friday\"}}x{{/calendar}}",
+        RenderErrorReason::HashTypeMismatchForName("calendar", "week_start".to_string(), "\"monday\" or \"sunday\"".to_string()).into()
+    )]
+    #[case(
+        "{{#calendar year=2025 month=6}}x{{/calendar}}",
+        Error::TooManyRepetitions { count: 42, max: 35 }.into()
+    )]
+    #[case(
+        "{{calendar year=2026 month=2}}",
+        RenderErrorReason::BlockContentRequired.into()
+    )]
+    fn wrong_calendar(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "calendar",
+            Box::new(CalendarHelper::default().with_max_count(35)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#every \"15m\" for=\"1h\"}}{{this}} {{/every}}",
+        "00:15 00:30 00:45 01:00 "
+    )]
+    #[case(
+        "{{#every \"15m\" for=\"1h\" from_zero=true}}{{@index}}{{@offset}} {{/every}}",
+        "000:00 100:15 200:30 300:45 401:00 "
+    )]
+    #[case(
+        "{{#every \"45s\" for=\"2m\"}}{{@offset}}/{{@seconds}} {{/every}}",
+        "00:00:45/45 00:01:30/90 "
+    )]
+    #[case(
+        "{{#every \"10h\" for=\"1d\" as |o|}}{{o}}{{@first}}{{@last}} {{/every}}",
+        "10:00truefalse 20:00falsetrue "
+    )]
+    #[case("{{#every \"1h\" for=\"30m\"}}x{{else}}none{{/every}}", "none")]
+    #[case(
+        "{{#every \"1h\" for=\"0s\" from_zero=true}}{{this}}{{/every}}",
+        "00:00"
+    )]
+    fn every(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("every", Box::new(EveryHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#every for=\"1h\"}}x{{/every}}",
+        RenderErrorReason::ParamNotFoundForIndex("every", 0).into()
+    )]
+    #[case(
+        "{{#every \"15m\"}}x{{/every}}",
+        RenderErrorReason::ParamNotFoundForName("every", "for".to_string()).into()
+    )]
+    #[case(
+        "{{#every \"0m\" for=\"1h\"}}x{{/every}}",
+        RenderErrorReason::ParamTypeMismatchForName("every", "0".to_string(), "non-zero duration".to_string()).into()
+    )]
+    #[case(
+        "{{#every 15 for=\"1h\"}}x{{/every}}",
+        RenderErrorReason::ParamTypeMismatchForName("every", "0".to_string(), "non-zero duration".to_string()).into()
+    )]
+    #[case(
+        "{{#every \"15m\" for=\"an hour\"}}x{{/every}}",
+        RenderErrorReason::HashTypeMismatchForName("every", "for".to_string(), "duration".to_string()).into()
+    )]
+    #[case(
+        "{{#every \"1s\" for=\"1h\"}}x{{/every}}",
+        Error::TooManyRepetitions { count: 3600, max: 100 }.into()
+    )]
+    fn wrong_every(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "every",
+            Box::new(EveryHelper::default().with_max_count(100)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#blocks \"4KiB\" block=\"1KiB\"}}{{this}}:{{@len}} {{/blocks}}",
+        "0:1024 1024:1024 2048:1024 3072:1024 "
+    )]
+    #[case(
+        "{{#blocks 1000 block=300}}{{@index}}|{{@offset}}|{{@len}}|{{@last}} {{/blocks}}",
+        "0|0|300|false 1|300|300|false 2|600|300|false 3|900|100|true "
+    )]
+    #[case(
+        "{{#blocks \"1 kB\" block=\"1KiB\" as |o|}}{{o}}{{@len}}{{@first}}{{@last}}{{/blocks}}",
+        "01000truetrue"
+    )]
+    #[case("{{#blocks 0 block=512}}x{{else}}empty{{/blocks}}", "empty")]
+    fn blocks(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("blocks", Box::new(BlocksHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#blocks block=512}}x{{/blocks}}",
+        RenderErrorReason::ParamNotFoundForIndex("blocks", 0).into()
+    )]
+    #[case(
+        "{{#blocks 1024}}x{{/blocks}}",
+        RenderErrorReason::ParamNotFoundForName("blocks", "block".to_string()).into()
+    )]
+    #[case(
+        "{{#blocks 1024 block=0}}x{{/blocks}}",
+        RenderErrorReason::HashTypeMismatchForName("blocks", "block".to_string(), "non-zero byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#blocks \"4 KB\" block=\"1 sector\"}}x{{/blocks}}",
+        RenderErrorReason::HashTypeMismatchForName("blocks", "block".to_string(), "byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#blocks -1 block=512}}x{{/blocks}}",
+        RenderErrorReason::ParamTypeMismatchForName("blocks", "0".to_string(), "byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#blocks \"1MiB\" block=512}}x{{/blocks}}",
+        Error::TooManyRepetitions { count: 2048, max: 100 }.into()
+    )]
+    fn wrong_blocks(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "blocks",
+            Box::new(BlocksHelper::default().with_max_count(100)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#offsets total=64}}{{@offset}} {{/offsets}}",
+        "0x00 0x10 0x20 0x30 "
+    )]
+    #[case(
+        "{{#offsets total=\"4KiB\" width=\"1KiB\"}}{{@offset}}={{this}} {{/offsets}}",
+        "0x000=0 0x400=1024 0x800=2048 0xc00=3072 "
+    )]
+    #[case("{{#offsets total=40 width=16 digits=4}}{{@index}}:{{@offset}}+{{@len}}{{#if @last}}!{{/if}} {{/offsets}}", "0:0x0000+16 1:0x0010+16 2:0x0020+8! ")]
+    #[case(
+        "{{#offsets total=4096 width=256 digits=1}}{{#if @last}}{{@offset}}{{/if}}{{/offsets}}",
+        "0xf00"
+    )]
+    #[case(
+        "{{#offsets total=16 digits=16}}{{@offset}}{{/offsets}}",
+        "0x0000000000000000"
+    )]
+    #[case("{{#offsets total=0}}x{{else}}empty{{/offsets}}", "empty")]
+    fn offsets(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("offsets", Box::new(OffsetsHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#offsets width=16}}x{{/offsets}}",
+        RenderErrorReason::ParamNotFoundForName("offsets", "total".to_string()).into()
+    )]
+    #[case(
+        "{{#offsets total=64 width=0}}x{{/offsets}}",
+        RenderErrorReason::HashTypeMismatchForName("offsets", "width".to_string(), "non-zero byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#offsets total=\"lots\"}}x{{/offsets}}",
+        RenderErrorReason::HashTypeMismatchForName("offsets", "total".to_string(), "byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#offsets total=64 digits=\"4\"}}x{{/offsets}}",
+        RenderErrorReason::HashTypeMismatchForName("offsets", "digits".to_string(), "at most 16 digits".to_string()).into()
+    )]
+    #[case(
+        "{{#offsets total=64 digits=17}}x{{/offsets}}",
+        RenderErrorReason::HashTypeMismatchForName("offsets", "digits".to_string(), "at most 16 digits".to_string()).into()
+    )]
+    #[case(
+        "{{#offsets total=\"1MiB\"}}x{{/offsets}}",
+        Error::TooManyRepetitions { count: 65536, max: 100 }.into()
+    )]
+    fn wrong_offsets(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        let helper = OffsetsHelper::from(BlocksHelper::default().with_max_count(100));
+        reg.register_helper("offsets", Box::new(helper));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#chunks total=1000 size=256}}{{@offset}}+{{@len}}{{#if @last}}!{{/if}} {{/chunks}}",
+        "0+256 256+256 512+256 768+232! "
+    )]
+    #[case(
+        "{{#chunks total=512 size=256}}{{@len}}{{@last}} {{/chunks}}",
+        "256false 256true "
+    )]
+    #[case(
+        "{{#chunks total=3 size=10 as |o|}}{{o}}{{@len}}{{@first}}{{@last}}{{/chunks}}",
+        "03truetrue"
+    )]
+    #[case(
+        "{{#chunks total=\"1KiB\" size=\"300B\"}}{{@index}}{{/chunks}}",
+        "0123"
+    )]
+    #[case("{{#chunks total=0 size=10}}x{{else}}none{{/chunks}}", "none")]
+    fn chunks(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("chunks", Box::new(ChunksHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#chunks size=10}}x{{/chunks}}",
+        RenderErrorReason::ParamNotFoundForName("chunks", "total".to_string()).into()
+    )]
+    #[case(
+        "{{#chunks total=10}}x{{/chunks}}",
+        RenderErrorReason::ParamNotFoundForName("chunks", "size".to_string()).into()
+    )]
+    #[case(
+        "{{#chunks total=10 size=0}}x{{/chunks}}",
+        RenderErrorReason::HashTypeMismatchForName("chunks", "size".to_string(), "non-zero u64".to_string()).into()
+    )]
+    #[case(
+        "{{#chunks total=-10 size=1}}x{{/chunks}}",
+        RenderErrorReason::HashTypeMismatchForName("chunks", "total".to_string(), "byte size".to_string()).into()
+    )]
+    #[case(
+        "{{#chunks total=1000 size=1}}x{{/chunks}}",
+        Error::TooManyRepetitions { count: 1000, max: 100 }.into()
+    )]
+    fn wrong_chunks(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        let helper = ChunksHelper::from(BlocksHelper::default().with_max_count(100));
+        reg.register_helper("chunks", Box::new(helper));
+        assert_fails(reg.render_template(template, &()), expected);
+    }
+
+    #[rstest]
+    #[case("{{#digits 4096}}{{this}},{{/digits}}", "4,0,9,6,")]
+    #[case("{{#digits 0}}{{@digit}}{{@first}}{{@last}}{{/digits}}", "0truetrue")]
+    #[case("{{#digits -42}}{{@digit}}{{/digits}}", "42")]
+    #[case("{{#digits 42 width=4}}{{@digit}}{{/digits}}", "0042")]
+    #[case("{{#digits 12345 width=2}}{{@digit}}{{/digits}}", "12345")]
+    #[case(
+        "{{#digits 7 width=65535}}{{#if @last}}{{@count}}{{/if}}{{/digits}}",
+        "65535"
+    )]
+    #[case(
+        "{{#digits \"0071\"}}{{@digit}}@{{@rindex}} {{/digits}}",
+        "0@3 0@2 7@1 1@0 "
+    )]
+    #[case(
+        "{{#digits \"123456789012345678901234567890\"}}{{#if @last}}{{@count}}{{/if}}{{/digits}}",
+        "30"
+    )]
+    #[case("{{#digits 798 as |d|}}{{d}}{{#if @even}}e{{/if}}{{/digits}}", "7e98e")]
+    fn digits(#[case] template: &str, #[case] output: &str) {
+        let mut reg = Handlebars::new();
+        reg.register_helper("digits", Box::new(DigitsHelper::default()));
+        assert_eq!(reg.render_template(template, &()).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(
+        "{{#digits}}x{{/digits}}",
+        RenderErrorReason::ParamNotFoundForIndex("digits", 0).into()
+    )]
+    #[case(
+        "{{#digits 1.5}}x{{/digits}}",
+        RenderErrorReason::ParamTypeMismatchForName("digits", "0".to_string(), "integer or string of digits".to_string()).into()
+    )]
+    #[case(
+        "{{#digits \"\"}}x{{/digits}}",
+        RenderErrorReason::ParamTypeMismatchForName("digits", "0".to_string(), "integer or string of digits".to_string()).into()
+    )]
+    #[case(
+        "{{#digits \"12a\"}}x{{/digits}}",
+        RenderErrorReason::ParamTypeMismatchForName("digits", "0".to_string(), "integer or string of digits".to_string()).into()
+    )]
+    #[case(
+        "{{#digits 12 width=\"3\"}}x{{/digits}}",
+        RenderErrorReason::HashTypeMismatchForName("digits", "width".to_string(), "at most 65535 digits".to_string()).into()
+    )]
+    #[case(
+        "{{#digits 12 width=18446744073709551615}}x{{/digits}}",
+        RenderErrorReason::HashTypeMismatchForName("digits", "width".to_string(), "at most 65535 digits".to_string()).into()
+    )]
+    #[case(
+        "{{#digits 12 width=11}}x{{/digits}}",
+        Error::TooManyRepetitions { count: 11, max: 10 }.into()
+    )]
+    #[case(
+        "{{digits 12}}",
+        RenderErrorReason::BlockContentRequired.into()
+    )]
+    fn wrong_digits(#[case] template: &str, #[case] expected: RenderError) {
+        let mut reg = Handlebars::new();
+        reg.register_helper(
+            "digits",
+            Box::new(DigitsHelper::default().with_max_count(10)),
+        );
+        assert_fails(reg.render_template(template, &()), expected);
     }
 }
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Locale-aware digit grouping of the index and names of the months
+
+/// The digit grouping conventions of a locale
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Locale {
+    separator: &'static str,
+    indian: bool,
+}
+
+impl Locale {
+    /// Looks up a locale by its language tag, such as `de` or `en-IN`
+    ///
+    /// Both `-` and `_` may separate the subtags, and the case is ignored.
+    /// When the full tag is unknown, the language alone is tried.
+    pub(crate) fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.to_ascii_lowercase().replace('_', "-");
+        let language = tag.split('-').next().unwrap_or("");
+        Self::find(&tag).or_else(|| Self::find(language))
+    }
+
+    fn find(tag: &str) -> Option<Self> {
+        let (separator, indian) = match tag {
+            "en-in" | "hi" | "bn" | "mr" | "ta" | "te" => (",", true),
+            "de-ch" | "de-li" | "it-ch" => ("\u{2019}", false),
+            "fr-ch" => ("\u{202f}", false),
+            "en" | "ja" | "ko" | "zh" | "he" | "th" => (",", false),
+            "de" | "it" | "nl" | "es" | "pt" | "da" | "id" | "tr" | "el" => (".", false),
+            "fr" => ("\u{202f}", false),
+            "ru" | "pl" | "cs" | "sk" | "uk" | "sv" | "fi" | "nb" | "hu" => ("\u{a0}", false),
+            _ => return None,
+        };
+
+        Some(Self { separator, indian })
+    }
+
+    /// Groups the digits of the number
+    ///
+    /// Digits are grouped in threes, except in the Indian system which groups
+    /// all but the last three digits in twos.
+    pub(crate) fn format(&self, n: u64) -> String {
+        let digits = n.to_string();
+        let mut head = digits.len().saturating_sub(3);
+        let mut groups = vec![&digits[head..]];
+
+        let size = if self.indian { 2 } else { 3 };
+        while head > 0 {
+            let start = head.saturating_sub(size);
+            groups.push(&digits[start..head]);
+            head = start;
+        }
+
+        groups.reverse();
+        groups.join(self.separator)
+    }
+}
+
+/// The names of the months in the language of a locale, from January
+///
+/// Only the language of the tag is considered, and the names are in the
+/// nominative case as they appear in headings.
+#[cfg(feature = "time")]
+pub(crate) fn months(tag: &str) -> Option<[&'static str; 12]> {
+    let tag = tag.to_ascii_lowercase().replace('_', "-");
+    let names = match tag.split('-').next().unwrap_or("") {
+        "en" => crate::date::MONTHS,
+        "de" => [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        "fr" => [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        "es" => [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        "it" => [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        "pt" => [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        "nl" => [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        "sv" => [
+            "januari",
+            "februari",
+            "mars",
+            "april",
+            "maj",
+            "juni",
+            "juli",
+            "augusti",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        "nb" => [
+            "januar",
+            "februar",
+            "mars",
+            "april",
+            "mai",
+            "juni",
+            "juli",
+            "august",
+            "september",
+            "oktober",
+            "november",
+            "desember",
+        ],
+        "da" => [
+            "januar",
+            "februar",
+            "marts",
+            "april",
+            "maj",
+            "juni",
+            "juli",
+            "august",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        "pl" => [
+            "styczeń",
+            "luty",
+            "marzec",
+            "kwiecień",
+            "maj",
+            "czerwiec",
+            "lipiec",
+            "sierpień",
+            "wrzesień",
+            "październik",
+            "listopad",
+            "grudzień",
+        ],
+        _ => return None,
+    };
+
+    Some(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("en", 0, "0")]
+    #[case("en", 999, "999")]
+    #[case("en", 1000, "1,000")]
+    #[case("en-US", 1234567, "1,234,567")]
+    #[case("de", 1000, "1.000")]
+    #[case("de_DE", 1234567, "1.234.567")]
+    #[case("de-CH", 1234567, "1\u{2019}234\u{2019}567")]
+    #[case("fr", 1000, "1\u{202f}000")]
+    #[case("ru", 1000, "1\u{a0}000")]
+    #[case("en-IN", 1234567, "12,34,567")]
+    #[case("hi", 100000, "1,00,000")]
+    #[case("en", u64::MAX, "18,446,744,073,709,551,615")]
+    fn grouped(#[case] tag: &str, #[case] n: u64, #[case] output: &str) {
+        assert_eq!(Locale::parse(tag).unwrap().format(n), output);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("xx")]
+    #[case("-de")]
+    fn unknown(#[case] tag: &str) {
+        assert_eq!(Locale::parse(tag), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[rstest]
+    #[case("en", 1, Some("February"))]
+    #[case("de-AT", 2, Some("März"))]
+    #[case("FR_ca", 7, Some("août"))]
+    #[case("xx", 0, None)]
+    fn month_names(#[case] tag: &str, #[case] month: usize, #[case] name: Option<&str>) {
+        assert_eq!(months(tag).map(|names| names[month]), name);
+    }
+}
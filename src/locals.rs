@@ -0,0 +1,396 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The local variables of each repetition
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use handlebars::template::{DecoratorTemplate, HelperTemplate, Parameter, TemplateElement};
+use handlebars::*;
+
+use crate::numerals;
+
+/// The local variables of a repetition, in the order they are set
+///
+/// A later variable of the same name takes precedence, as it would when set
+/// upon the block.
+#[derive(Default)]
+pub(crate) struct Locals(Vec<(Cow<'static, str>, JsonValue)>);
+
+impl Locals {
+    /// Sets a local variable
+    pub(crate) fn set(&mut self, name: impl Into<Cow<'static, str>>, value: impl Into<JsonValue>) {
+        self.0.push((name.into(), value.into()));
+    }
+
+    /// Sets the local variables of another, after those already set
+    pub(crate) fn extend(&mut self, other: Locals) {
+        self.0.extend(other.0);
+    }
+
+    /// Sets the members of an object as local variables named after the
+    /// prefix, such as `outer_index` for the `index` member of `outer`, or
+    /// only the named members, if given
+    pub(crate) fn flatten(
+        &mut self,
+        prefix: &str,
+        object: &JsonValue,
+        names: Option<&BTreeSet<&str>>,
+    ) {
+        if let JsonValue::Object(members) = object {
+            let mut set = |name: &str, value: &JsonValue| {
+                self.set(format!("{}_{}", prefix, name), value.clone());
+            };
+
+            match names {
+                None => members.iter().for_each(|(name, value)| set(name, value)),
+                Some(names) => names
+                    .iter()
+                    .filter_map(|name| Some((*name, members.get(*name)?)))
+                    .for_each(|(name, value)| set(name, value)),
+            }
+        }
+    }
+
+    /// The local variables as an object
+    pub(crate) fn to_json(&self) -> JsonValue {
+        let members = self
+            .0
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()));
+        JsonValue::Object(members.collect())
+    }
+}
+
+impl IntoIterator for Locals {
+    type Item = (Cow<'static, str>, JsonValue);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// The words with which a block may read the local variables of its
+/// repetitions
+///
+/// With lazy local variables, only those named by a word of the block are
+/// built. The words are those of the local variables, paths and strings within
+/// the block, the partials it names and the parameters of its helper, so that
+/// `{{lookup @outer "alpha"}}` or `until="gt @index 3"` still reads its local
+/// variable. A partial chosen by a subexpression could read any of them.
+pub(crate) enum Uses {
+    /// Any local variable may be read
+    All,
+
+    /// Only the local variables named by one of the words may be read
+    Words(BTreeSet<String>),
+}
+
+impl Uses {
+    /// Collects the words of a helper, its block and its inverse
+    pub(crate) fn new(
+        h: &Helper<'_>,
+        templates: &[Option<&Template>],
+        r: &Handlebars<'_>,
+        rc: &RenderContext<'_, '_>,
+    ) -> Self {
+        let mut walk = Walk::default();
+        for param in h.params().iter().chain(h.hash().values()) {
+            if let Some(path) = param.relative_path() {
+                walk.text(path);
+            }
+
+            if let Some(text) = param.value().as_str() {
+                walk.text(text);
+            }
+        }
+
+        for template in templates.iter().flatten() {
+            walk.template(template);
+        }
+
+        let mut seen = BTreeSet::new();
+        while let Some(name) = walk.partials.pop_first() {
+            if seen.insert(name.clone()) {
+                if let Some(partial) = rc.get_partial(&name).or_else(|| r.get_template(&name)) {
+                    walk.template(partial);
+                }
+            }
+        }
+
+        match walk.dynamic {
+            true => Self::All,
+            false => Self::Words(walk.words),
+        }
+    }
+
+    /// Whether a local variable of the given name may be read
+    pub(crate) fn has(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Words(words) => words.contains(name),
+        }
+    }
+
+    /// Reads the local variables under the prefix, as well as their own names
+    pub(crate) fn unprefix(&mut self, prefix: &str) {
+        if let Self::Words(words) = self {
+            let names: Vec<_> = words
+                .iter()
+                .filter_map(|word| word.strip_prefix(prefix))
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+            words.extend(names);
+        }
+    }
+
+    /// Reads a local variable under another name, as well as its own
+    pub(crate) fn rename(&mut self, name: &str, rename: &str) {
+        if let Self::Words(words) = self {
+            if words.contains(rename) {
+                words.insert(name.into());
+            }
+        }
+    }
+
+    /// Sets a local variable, computing its value, provided it may be read
+    pub(crate) fn put<T: Into<JsonValue>>(
+        &self,
+        locals: &mut Locals,
+        name: &'static str,
+        value: impl FnOnce() -> T,
+    ) {
+        if self.has(name) {
+            locals.set(name, value());
+        }
+    }
+
+    /// The names which follow the prefix within the words, such as `index`
+    /// for `outer_` within `outer_index`, or every name when any may be read
+    ///
+    /// A repeated prefix is followed by each of the names, so that
+    /// `outer_outer_index` gives both `outer_index` and `index`.
+    pub(crate) fn following(&self, prefix: &str) -> Option<BTreeSet<&str>> {
+        match self {
+            Self::All => None,
+            Self::Words(words) => Some(
+                words
+                    .iter()
+                    .flat_map(|word| {
+                        std::iter::successors(word.strip_prefix(prefix), |name| {
+                            name.strip_prefix(prefix)
+                        })
+                    })
+                    .filter(|name| !name.is_empty())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// The words, partial names and subexpression partials found within templates
+#[derive(Default)]
+struct Walk {
+    words: BTreeSet<String>,
+    partials: BTreeSet<String>,
+    dynamic: bool,
+}
+
+impl Walk {
+    fn text(&mut self, text: &str) {
+        let words = text.split(|c: char| !(c.is_alphanumeric() || c == '_'));
+        self.words
+            .extend(words.filter(|w| !w.is_empty()).map(String::from));
+    }
+
+    fn template(&mut self, template: &Template) {
+        for element in &template.elements {
+            self.element(element);
+        }
+    }
+
+    fn element(&mut self, element: &TemplateElement) {
+        match element {
+            TemplateElement::RawString(..) | TemplateElement::Comment(..) => (),
+
+            TemplateElement::HtmlExpression(ht)
+            | TemplateElement::Expression(ht)
+            | TemplateElement::HelperBlock(ht) => self.helper(ht),
+
+            TemplateElement::DecoratorExpression(dt) | TemplateElement::DecoratorBlock(dt) => {
+                self.decorator(dt)
+            }
+
+            TemplateElement::PartialExpression(dt) | TemplateElement::PartialBlock(dt) => {
+                match &dt.name {
+                    Parameter::Subexpression(..) => self.dynamic = true,
+                    Parameter::Literal(JsonValue::String(name)) => {
+                        self.partials.insert(name.clone());
+                    }
+                    name => {
+                        if let Some(name) = name.as_name() {
+                            self.partials.insert(name.to_string());
+                        }
+                    }
+                }
+
+                self.decorator(dt)
+            }
+        }
+    }
+
+    fn helper(&mut self, ht: &HelperTemplate) {
+        self.parameter(&ht.name);
+        for param in ht.params.iter().chain(ht.hash.values()) {
+            self.parameter(param);
+        }
+
+        for template in ht.template.iter().chain(&ht.inverse) {
+            self.template(template);
+        }
+    }
+
+    fn decorator(&mut self, dt: &DecoratorTemplate) {
+        for param in dt.params.iter().chain(dt.hash.values()) {
+            self.parameter(param);
+        }
+
+        if let Some(template) = &dt.template {
+            self.template(template);
+        }
+    }
+
+    fn parameter(&mut self, param: &Parameter) {
+        match param {
+            Parameter::Name(name) => self.text(name),
+            Parameter::Path(..) => self.text(param.as_name().unwrap_or_default()),
+            Parameter::Literal(JsonValue::String(text)) => self.text(text),
+            Parameter::Literal(..) => (),
+            Parameter::Subexpression(subexpression) => self.element(&subexpression.element),
+        }
+    }
+}
+
+/// The position of a repetition, from which its local variables are derived
+pub(crate) struct Position {
+    /// The position in the order of rendering
+    pub n: u64,
+
+    /// The position in the sequence, which differs from `n` when reversed
+    pub i: u64,
+
+    /// The value of `@index`
+    pub index: u64,
+
+    /// The number of repetitions
+    pub count: u64,
+
+    /// The first rendered position
+    pub skip: u64,
+
+    /// The position after the last rendered one
+    pub end: u64,
+}
+
+impl Position {
+    /// Sets the local variables of the repetition which may be read
+    ///
+    /// `@index`, `@first` and `@last` are always set, for helpers which read
+    /// them from the block.
+    pub(crate) fn set(&self, locals: &mut Locals, uses: &Uses) {
+        let first = self.n == self.skip;
+        let last = self.n == self.end - 1;
+        locals.set("index", self.index);
+        locals.set("first", first);
+        locals.set("last", last);
+
+        if let Some(index1) = self.index.checked_add(1) {
+            uses.put(locals, "index1", || index1);
+        }
+
+        uses.put(locals, "index0", || {
+            let width = self.count.to_string().len();
+            format!("{:0width$}", self.index, width = width)
+        });
+
+        if uses.has("alpha") || uses.has("ALPHA") {
+            let alpha = numerals::alpha(self.index);
+            locals.set("ALPHA", alpha.to_uppercase());
+            locals.set("alpha", alpha);
+        }
+
+        if uses.has("roman") || uses.has("ROMAN") {
+            if let Some(roman) = numerals::roman(self.index.saturating_add(1)) {
+                locals.set("ROMAN", roman.to_uppercase());
+                locals.set("roman", roman);
+            }
+        }
+
+        uses.put(locals, "ordinal", || {
+            numerals::ordinal(self.index.saturating_add(1))
+        });
+        uses.put(locals, "rindex", || self.count - self.i - 1);
+        uses.put(locals, "middle", || !first && !last);
+        uses.put(locals, "phase", || match (first, last) {
+            (true, true) => "only",
+            (true, false) => "start",
+            (false, true) => "end",
+            (false, false) => "middle",
+        });
+        uses.put(locals, "even", || self.n % 2 == 0);
+        uses.put(locals, "odd", || self.n % 2 == 1);
+        uses.put(locals, "count", || self.count);
+        // Only the rest of the window remains, unlike for `rindex`.
+        uses.put(locals, "remaining", || self.end - self.n - 1);
+
+        let done = self.n + 1;
+        uses.put(locals, "progress", || done as f64 / self.count as f64);
+        uses.put(locals, "percent", || {
+            (u128::from(done) * 100 / u128::from(self.count)) as u64
+        });
+        uses.put(locals, "parity", || match self.n % 2 {
+            0 => "even",
+            _ => "odd",
+        });
+        uses.put(locals, "index_str", || self.index.to_string());
+    }
+}
+
+/// Sanitizes a prefix for use within a C identifier
+///
+/// Characters other than ASCII letters, digits and underscores are replaced
+/// with underscores, and a leading digit is preceded by one.
+pub(crate) fn identifier(prefix: &str) -> String {
+    let mut ident: String = prefix
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+
+    ident
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("item", "item")]
+    #[case("my-item", "my_item")]
+    #[case("2d", "_2d")]
+    #[case("naïve.x", "na_ve_x")]
+    #[case("A_b9", "A_b9")]
+    fn identifiers(#[case] prefix: &str, #[case] output: &str) {
+        assert_eq!(identifier(prefix), output);
+    }
+}
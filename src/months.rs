@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `months` helper
+
+use handlebars::*;
+
+use crate::date::{Date, Step, MONTHS};
+use crate::each;
+
+/// The `months` handler object
+///
+/// `{{#months from="2024-01" to="2024-12"}}` renders its block once for each
+/// month from the `from` month up to and including the `to` month, both in the
+/// `YYYY-MM` form. Each repetition is rendered with its month, in that form, as
+/// the context and the block parameter, if any. The `@year`, `@month` (from 1)
+/// and `@month_name` local variables describe the month, along with `@index`,
+/// `@first` and `@last`. When no months are in range, the inverse block is
+/// rendered.
+///
+/// The month names are in English, unless the `i18n` feature is enabled and
+/// the `locale` hash parameter gives a language tag, such as `de` or `fr-CA`,
+/// whose month names are known. To use, register it in your handlebars
+/// registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("months", Box::new(handlebars_repeat::MonthsHelper::default()));
+///
+/// let template = "{{#months from=\"2023-11\" to=\"2024-02\"}}{{@month_name}} {{@year}}\n{{/months}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "November 2023\nDecember 2023\nJanuary 2024\nFebruary 2024\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MonthsHelper {
+    max_count: Option<u64>,
+}
+
+impl MonthsHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// Parses the named hash parameter as the first day of a month
+fn month(h: &Helper<'_>, name: &'static str) -> Result<Date, RenderError> {
+    let value = h
+        .hash_get(name)
+        .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("months", name.to_string()))?;
+
+    let date = match value.value().as_str() {
+        Some(month) if month.len() == 7 => Date::parse(&format!("{}-01", month)),
+        _ => None,
+    };
+
+    date.ok_or_else(|| {
+        RenderErrorReason::HashTypeMismatchForName("months", name.to_string(), "month".to_string())
+            .into()
+    })
+}
+
+impl HelperDef for MonthsHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let from = month(h, "from")?;
+        let to = month(h, "to")?;
+
+        #[allow(unused_mut)]
+        let mut names = MONTHS;
+        #[cfg(feature = "i18n")]
+        if let Some(locale) = h.hash_get("locale") {
+            names = locale
+                .value()
+                .as_str()
+                .and_then(crate::locale::months)
+                .ok_or_else(|| {
+                    RenderErrorReason::HashTypeMismatchForName(
+                        "months",
+                        "locale".to_string(),
+                        "known locale".to_string(),
+                    )
+                })?;
+        }
+
+        let step = Step::Months(1);
+        let count = step.count(&from, &to);
+        let items = (0..count).map(|n| {
+            let date = step.nth(&from, n as i64);
+
+            let mut item = each::Item::new(format!("{:04}-{:02}", date.year, date.month));
+            item.locals.set("year", date.year);
+            item.locals.set("month", date.month);
+            item.locals
+                .set("month_name", names[date.month as usize - 1]);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
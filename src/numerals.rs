@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Alternative renderings of the index
+
+/// Returns the bijective base-26 lowercase letters for a number
+///
+/// Zero is `a`, 25 is `z`, 26 is `aa` and so on, as in spreadsheet columns.
+pub(crate) fn alpha(n: u64) -> String {
+    let mut letters = Vec::new();
+    let mut n = u128::from(n) + 1;
+
+    while n > 0 {
+        n -= 1;
+        letters.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+/// Returns the lowercase roman numeral for a number from 1 to 3999
+pub(crate) fn roman(mut n: u64) -> Option<String> {
+    if !(1..=3999).contains(&n) {
+        return None;
+    }
+
+    const NUMERALS: &[(u64, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut numeral = String::new();
+    for (value, letters) in NUMERALS {
+        while n >= *value {
+            numeral.push_str(letters);
+            n -= value;
+        }
+    }
+
+    Some(numeral)
+}
+
+/// Returns the English ordinal for a number, such as `1st` or `12th`
+pub(crate) fn ordinal(n: u64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, "a")]
+    #[case(1, "b")]
+    #[case(25, "z")]
+    #[case(26, "aa")]
+    #[case(27, "ab")]
+    #[case(51, "az")]
+    #[case(52, "ba")]
+    #[case(701, "zz")]
+    #[case(702, "aaa")]
+    #[case(u64::MAX, "gkgwbylwrxtlpp")]
+    fn letters(#[case] n: u64, #[case] output: &str) {
+        assert_eq!(alpha(n), output);
+    }
+
+    #[rstest]
+    #[case(1, "i")]
+    #[case(3, "iii")]
+    #[case(4, "iv")]
+    #[case(9, "ix")]
+    #[case(14, "xiv")]
+    #[case(40, "xl")]
+    #[case(90, "xc")]
+    #[case(400, "cd")]
+    #[case(1994, "mcmxciv")]
+    #[case(3999, "mmmcmxcix")]
+    fn numerals(#[case] n: u64, #[case] output: &str) {
+        assert_eq!(roman(n).unwrap(), output);
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(4000)]
+    #[case(u64::MAX)]
+    fn unrepresentable(#[case] n: u64) {
+        assert_eq!(roman(n), None);
+    }
+
+    #[rstest]
+    #[case(0, "0th")]
+    #[case(1, "1st")]
+    #[case(2, "2nd")]
+    #[case(3, "3rd")]
+    #[case(4, "4th")]
+    #[case(11, "11th")]
+    #[case(12, "12th")]
+    #[case(13, "13th")]
+    #[case(21, "21st")]
+    #[case(102, "102nd")]
+    #[case(111, "111th")]
+    #[case(1013, "1013th")]
+    fn ordinals(#[case] n: u64, #[case] output: &str) {
+        assert_eq!(ordinal(n), output);
+    }
+}
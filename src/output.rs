@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Output adapters applied to the rendered block
+
+use std::cell::Cell;
+use std::io::Error as IOError;
+
+use handlebars::Output;
+
+/// Prefixes every non-empty line written through it
+pub(crate) struct Indent<'a> {
+    out: &'a mut dyn Output,
+    indent: &'a str,
+    start: bool,
+}
+
+impl<'a> Indent<'a> {
+    pub(crate) fn new(out: &'a mut dyn Output, indent: &'a str) -> Self {
+        Self {
+            out,
+            indent,
+            start: true,
+        }
+    }
+}
+
+impl Output for Indent<'_> {
+    fn write(&mut self, seg: &str) -> Result<(), IOError> {
+        for line in seg.split_inclusive('\n') {
+            if self.start && line != "\n" && line != "\r\n" {
+                self.out.write(self.indent)?;
+            }
+
+            self.out.write(line)?;
+            self.start = line.ends_with('\n');
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the bytes written through it
+pub(crate) struct Count<'a> {
+    out: &'a mut dyn Output,
+    bytes: &'a Cell<u64>,
+}
+
+impl<'a> Count<'a> {
+    pub(crate) fn new(out: &'a mut dyn Output, bytes: &'a Cell<u64>) -> Self {
+        Self { out, bytes }
+    }
+}
+
+impl Output for Count<'_> {
+    fn write(&mut self, seg: &str) -> Result<(), IOError> {
+        self.out.write(seg)?;
+        self.bytes
+            .set(self.bytes.get().saturating_add(seg.len() as u64));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use handlebars::StringOutput;
+    use rstest::*;
+
+    #[rstest]
+    #[case(&["a"], "  a")]
+    #[case(&["a\nb\n"], "  a\n  b\n")]
+    #[case(&["a", "b\n", "c"], "  ab\n  c")]
+    #[case(&["a\n", "\n", "b"], "  a\n\n  b")]
+    #[case(&["", "a\n\r\nb"], "  a\n\r\n  b")]
+    fn indent(#[case] segments: &[&str], #[case] output: &str) {
+        let mut string = StringOutput::new();
+        let mut indent = Indent::new(&mut string, "  ");
+        for segment in segments {
+            indent.write(segment).unwrap();
+        }
+
+        assert_eq!(string.into_string().unwrap(), output);
+    }
+
+    #[test]
+    fn count() {
+        let bytes = Cell::new(0);
+        let mut string = StringOutput::new();
+        let mut count = Count::new(&mut string, &bytes);
+        count.write("ab").unwrap();
+        count.write("").unwrap();
+        count.write("é").unwrap();
+
+        assert_eq!(bytes.get(), 4);
+        assert_eq!(string.into_string().unwrap(), "abé");
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic pseudo-random numbers
+//!
+//! This implements SplitMix64 rather than depending on an external crate so
+//! that seeded output remains stable across releases.
+
+#[cfg(feature = "uuid")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "uuid")]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(feature = "uuid")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "uuid")]
+use std::sync::Once;
+
+/// The increment of the SplitMix64 state
+#[cfg(feature = "uuid")]
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Returns the SplitMix64 output for the given state
+pub(crate) fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Returns a pseudo-random number in `min..=max`
+#[cfg(feature = "rand")]
+pub(crate) fn between(seed: u64, min: u64, max: u64) -> u64 {
+    match (max - min).checked_add(1) {
+        Some(span) => min + splitmix64(seed) % span,
+        None => splitmix64(seed),
+    }
+}
+
+/// Returns a pseudo-random number in `0.0..1.0` for the given seed and index
+#[cfg(feature = "rand")]
+pub(crate) fn unit(seed: u64, index: u64) -> f64 {
+    let bits = splitmix64(seed ^ splitmix64(index)) >> 11;
+    bits as f64 / (1u64 << 53) as f64
+}
+
+/// Returns the next number of a SplitMix64 generator shared by the process
+///
+/// The generator is seeded once, from the random keys of the standard
+/// library's `RandomState`, and then advances its state by a fixed odd
+/// increment. Its state therefore repeats only after 2^64 numbers, and, since
+/// SplitMix64 mixes each state bijectively, so do its numbers.
+#[cfg(feature = "uuid")]
+pub(crate) fn next() -> u64 {
+    static SEEDED: Once = Once::new();
+    static STATE: AtomicU64 = AtomicU64::new(0);
+
+    SEEDED.call_once(|| {
+        let seed = RandomState::new().build_hasher().finish();
+        STATE.store(seed, Ordering::Relaxed);
+    });
+
+    splitmix64(STATE.fetch_add(GAMMA, Ordering::Relaxed))
+}
+
+/// Returns a hexadecimal digest of the seed and index
+pub(crate) fn digest(seed: u64, index: u64) -> String {
+    format!("{:016x}", splitmix64(splitmix64(seed).wrapping_add(index)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, 0xe220_a839_7b1d_cdaf)]
+    #[case(1, 0x910a_2dec_8902_5cc1)]
+    fn splitmix(#[case] state: u64, #[case] output: u64) {
+        assert_eq!(splitmix64(state), output);
+    }
+
+    #[cfg(feature = "rand")]
+    #[rstest]
+    #[case(2, 8)]
+    #[case(5, 5)]
+    #[case(0, u64::MAX)]
+    fn bounds(#[case] min: u64, #[case] max: u64) {
+        for seed in 0..100 {
+            let value = between(seed, min, max);
+            assert!(min <= value && value <= max);
+        }
+    }
+
+    #[test]
+    fn digests() {
+        assert_eq!(digest(0, 0).len(), 16);
+        assert_eq!(digest(0, 0), digest(0, 0));
+        assert_ne!(digest(0, 0), digest(0, 1));
+        assert_ne!(digest(0, 0), digest(1, 0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn units() {
+        for index in 0..100 {
+            let value = unit(42, index);
+            assert!((0.0..1.0).contains(&value));
+            assert_eq!(value, unit(42, index));
+            assert_ne!(value, unit(43, index));
+        }
+    }
+}
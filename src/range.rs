@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `range` helper
+
+use handlebars::*;
+
+use crate::RepeatHelper;
+
+/// The `range` handler object
+///
+/// It iterates over the range from its first parameter (inclusive) to its
+/// second (exclusive), by the step given as an optional third parameter. Each
+/// repetition is rendered with its `@index` as the context, and otherwise as
+/// by `repeat`, with the same hash parameters and local variables. To use,
+/// register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("range", Box::new(handlebars_repeat::RangeHelper::default()));
+///
+/// let template = "{{#range 1 10 2}}{{this}}{{#unless @last}},{{/unless}}{{/range}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "1,3,5,7,9");
+/// ```
+///
+/// As a subexpression, it instead returns the array of values, so that
+/// `(range 0 5)` is `[0, 1, 2, 3, 4]`:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("range", Box::new(handlebars_repeat::RangeHelper::default()));
+///
+/// let template = "{{#each (range 0 10 3)}}{{this}};{{/each}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "0;3;6;9;");
+/// ```
+///
+/// A configured `RepeatHelper` may be converted into a `RangeHelper`:
+///
+/// ```rust
+/// use handlebars_repeat::{RangeHelper, RepeatHelper};
+///
+/// let helper = RangeHelper::from(RepeatHelper::default().with_max_count(100));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RangeHelper(RepeatHelper);
+
+impl Default for RangeHelper {
+    fn default() -> Self {
+        RepeatHelper::default().into()
+    }
+}
+
+impl From<RepeatHelper> for RangeHelper {
+    fn from(helper: RepeatHelper) -> Self {
+        Self(helper.with_range(true))
+    }
+}
+
+impl HelperDef for RangeHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        self.0.call(h, r, ctx, rc, out)
+    }
+
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        Ok(ScopedJson::Derived(self.0.indices(h, ctx)?))
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sections chained to the block, such as `{{else after}}`
+
+use handlebars::template::{Template, TemplateElement};
+
+/// The sections of a block
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Sections<'a> {
+    /// Rendered once before the repetitions
+    pub before: Option<&'a Template>,
+
+    /// Rendered in place of the block when the count is one
+    pub single: Option<&'a Template>,
+
+    /// Rendered once after the repetitions
+    pub after: Option<&'a Template>,
+
+    /// Rendered when there are no repetitions
+    pub inverse: Option<&'a Template>,
+}
+
+impl<'a> Sections<'a> {
+    /// Splits the named sections from the chain of inverse blocks
+    ///
+    /// The chain ends at the first `{{else}}` which is not a known section,
+    /// so that `{{else if ...}}` and the like remain the inverse block.
+    pub(crate) fn parse(mut inverse: Option<&'a Template>) -> Self {
+        let mut sections = Self::default();
+
+        while let Some(template) = inverse {
+            let helper = match template.elements.as_slice() {
+                [TemplateElement::HelperBlock(helper)] if helper.chain => helper,
+                _ => break,
+            };
+
+            let section = match helper.name.as_name() {
+                Some("before") => &mut sections.before,
+                Some("single") => &mut sections.single,
+                Some("after") => &mut sections.after,
+                _ => break,
+            };
+
+            *section = helper.template.as_ref();
+            inverse = helper.inverse.as_ref();
+        }
+
+        sections.inverse = inverse;
+        sections
+    }
+}
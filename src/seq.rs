@@ -0,0 +1,243 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `seq` helper
+
+use std::convert::TryFrom;
+
+use handlebars::*;
+
+use crate::{each, format};
+
+/// The `seq` handler object
+///
+/// It mirrors `seq(1)`: `{{#seq last}}`, `{{#seq first last}}` and
+/// `{{#seq first incr last}}` count from `first` (default: 1) by `incr`
+/// (default: 1) up to and including `last`. The increment may be negative, in
+/// which case the sequence counts down.
+///
+/// Each repetition is rendered with its value as the context, which is
+/// formatted as a string when the `format` hash parameter gives a printf-style
+/// format (see `repeat`). The value is also the `@value` local variable and
+/// the block parameter, if any, and otherwise the local variables are those of
+/// `repeat`. An empty sequence renders the inverse block.
+///
+/// As for `seq(1)`, the bounds and increment may have up to nine decimal
+/// places, as in `{{#seq 0 0.25 1}}`. The values are then computed exactly in
+/// those places and rendered with as many as the most precise of them has:
+/// `0.00`, `0.25` and so on, while `@value` holds them as numbers. A `format`
+/// applies only to a sequence of integers. To use, register it in your
+/// handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("seq", Box::new(handlebars_repeat::SeqHelper::default()));
+///
+/// let template = "{{#seq 10 -3 1 format=\"node%02d\"}}{{this}} {{/seq}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "node10 node07 node04 node01 ");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SeqHelper {
+    max_count: Option<u64>,
+}
+
+impl SeqHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// The most decimal places of a bound or increment
+const MAX_PLACES: u32 = 9;
+
+/// The decimal places of a number, unless it has more than `MAX_PLACES`
+fn places(value: &JsonValue) -> Option<u32> {
+    if value.is_i64() {
+        return Some(0);
+    }
+
+    let number = value.as_f64().filter(|_| !value.is_u64())?;
+    (0..=MAX_PLACES).find(|places| {
+        let scale = 10f64.powi(*places as i32);
+        (number * scale).round() / scale == number
+    })
+}
+
+/// Scales a number to a whole number of the given decimal places, if it fits
+fn scaled(value: &JsonValue, places: u32) -> Option<i64> {
+    if let Some(integer) = value.as_i64() {
+        return integer.checked_mul(10i64.pow(places));
+    }
+
+    // Beyond 2^53, a float no longer holds every whole number.
+    let number = (value.as_f64()? * 10f64.powi(places as i32)).round();
+    match number.abs() < (1u64 << 53) as f64 {
+        true => Some(number as i64),
+        false => None,
+    }
+}
+
+fn param<'a>(h: &'a Helper<'_>, index: usize) -> Result<&'a JsonValue, RenderError> {
+    let value = h
+        .param(index)
+        .ok_or(RenderErrorReason::ParamNotFoundForIndex("seq", index))?;
+
+    match places(value.value()) {
+        Some(..) => Ok(value.value()),
+        None => Err(RenderErrorReason::ParamTypeMismatchForName(
+            "seq",
+            index.to_string(),
+            "number".to_string(),
+        )
+        .into()),
+    }
+}
+
+impl HelperDef for SeqHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let one = JsonValue::from(1);
+        let (first, incr, last) = match h.params().len() {
+            1 => (&one, &one, param(h, 0)?),
+            2 => (param(h, 0)?, &one, param(h, 1)?),
+            _ => (param(h, 0)?, param(h, 1)?, param(h, 2)?),
+        };
+
+        self.render(first, incr, last, h, r, ctx, rc, out)
+    }
+}
+
+impl SeqHelper {
+    /// Renders the sequence from `first` by `incr` to `last`, inclusive
+    #[allow(clippy::too_many_arguments)]
+    fn render<'reg: 'rc, 'rc>(
+        &self,
+        first: &JsonValue,
+        incr: &JsonValue,
+        last: &JsonValue,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let places = [first, incr, last]
+            .iter()
+            .filter_map(|value| places(value))
+            .max()
+            .unwrap_or(0);
+
+        let scale = |value: &JsonValue, index: usize| {
+            scaled(value, places).ok_or_else(|| {
+                RenderErrorReason::ParamTypeMismatchForName(
+                    "seq",
+                    index.to_string(),
+                    "number".to_string(),
+                )
+            })
+        };
+        let (first, incr, last) = (scale(first, 0)?, scale(incr, 1)?, scale(last, 2)?);
+
+        if incr == 0 {
+            return Err(RenderErrorReason::ParamTypeMismatchForName(
+                "seq",
+                "1".to_string(),
+                "non-zero number".to_string(),
+            )
+            .into());
+        }
+
+        let format = match h.hash_get("format") {
+            None => None,
+            Some(format) => match format.value().as_str().and_then(format::Format::parse) {
+                Some(format) if places == 0 => Some(format),
+                _ => {
+                    return Err(RenderErrorReason::HashTypeMismatchForName(
+                        "seq",
+                        "format".to_string(),
+                        "printf-style format of integers".to_string(),
+                    )
+                    .into())
+                }
+            },
+        };
+
+        let span = i128::from(last) - i128::from(first);
+        let count = match span / i128::from(incr) {
+            _ if span != 0 && (span < 0) != (incr < 0) => 0,
+            steps => u64::try_from(steps + 1).unwrap_or(u64::MAX),
+        };
+
+        let divisor = 10u64.pow(places);
+        let items = (0..count).map(|n| {
+            // The value is within the bounds, so it fits.
+            let value = (i128::from(first) + i128::from(n) * i128::from(incr)) as i64;
+
+            let (context, value): (JsonValue, JsonValue) = match (&format, places) {
+                (Some(format), ..) => (format.format_signed(value).into(), value.into()),
+                (None, 0) => (value.into(), value.into()),
+                (None, places) => {
+                    let sign = if value < 0 { "-" } else { "" };
+                    let decimal = format!(
+                        "{}{}.{:0places$}",
+                        sign,
+                        value.unsigned_abs() / divisor,
+                        value.unsigned_abs() % divisor,
+                        places = places as usize
+                    );
+                    (decimal.into(), (value as f64 / divisor as f64).into())
+                }
+            };
+
+            let mut item = each::Item::new(context);
+            item.param = Some(value.clone());
+            item.locals.set("value", value);
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
+
+/// The `countdown` handler object
+///
+/// `{{#countdown 5}}` counts down from 5 to 1, exactly as `{{#seq 5 -1 1}}`
+/// does (see [`SeqHelper`]). To use, register it in your handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("countdown", Box::new(handlebars_repeat::CountdownHelper::default()));
+///
+/// let template = "{{#countdown 3}}{{this}}... {{/countdown}}liftoff";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "3... 2... 1... liftoff");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CountdownHelper(SeqHelper);
+
+impl From<SeqHelper> for CountdownHelper {
+    fn from(helper: SeqHelper) -> Self {
+        Self(helper)
+    }
+}
+
+impl HelperDef for CountdownHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let (incr, last) = (JsonValue::from(-1), JsonValue::from(1));
+        self.0
+            .render(param(h, 0)?, &incr, &last, h, r, ctx, rc, out)
+    }
+}
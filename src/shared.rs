@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Named state shared by the blocks of a render
+//!
+//! Counters and accumulators must outlive any one block, yet must not leak
+//! from one render into the next. The state of a render is therefore held by
+//! a local helper, which the copies of the render context share and which
+//! ends with the render. Handlebars offers no means to reach a local helper
+//! but to call it, so the helper answers any call with its state.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use handlebars::*;
+
+/// The state of a render
+#[derive(Default)]
+struct State {
+    global: u64,
+    counters: BTreeMap<String, u64>,
+    accumulators: JsonValue,
+    frames: u64,
+    accumulating: BTreeMap<u64, JsonValue>,
+}
+
+/// The name of the local helper holding the state of the render
+const STORE: &str = "repeat-state";
+
+/// The state of a render, shared by the helpers acting upon it
+#[derive(Clone, Default)]
+pub(crate) struct Shared(Arc<Mutex<State>>);
+
+impl fmt::Debug for Shared {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Shared {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the state of the render")
+    }
+}
+
+impl std::error::Error for Shared {}
+
+/// The local helper holding the state of the render
+struct Store(Shared);
+
+impl HelperDef for Store {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        _: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        Err(RenderErrorReason::NestedError(Box::new(self.0.clone())).into())
+    }
+}
+
+impl Shared {
+    /// The state of the render, which begins with the first helper to ask
+    pub(crate) fn of<'reg: 'rc, 'rc>(
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+    ) -> Self {
+        if let Some(store) = rc.get_local_helper(STORE) {
+            if let Err(err) = store.call_inner(h, r, ctx, rc) {
+                if let RenderErrorReason::NestedError(err) = err.reason() {
+                    if let Some(shared) = err.downcast_ref::<Self>() {
+                        return shared.clone();
+                    }
+                }
+            }
+        }
+
+        let shared = Self::default();
+        rc.register_local_helper(STORE, Box::new(Store(shared.clone())));
+        shared
+    }
+
+    /// Applies a function to the state of the render
+    fn with<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
+        f(&mut self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// The global index of the next repetition of the render
+    pub(crate) fn global(&self) -> u64 {
+        self.with(|state| state.global)
+    }
+
+    /// Counts a repetition which began at the global index, unless the
+    /// repetitions nested within it were counted instead
+    pub(crate) fn counted(&self, global: u64) {
+        self.with(|state| {
+            if state.global == global {
+                state.global += 1;
+            }
+        })
+    }
+
+    /// Advances the named counter of the render, returning its new value
+    pub(crate) fn next(&self, name: &str) -> u64 {
+        self.with(|state| {
+            let counter = state.counters.entry(name.to_string()).or_default();
+            *counter += 1;
+            *counter
+        })
+    }
+
+    /// Allocates the identifier of a new frame
+    pub(crate) fn frame(&self) -> u64 {
+        self.with(|state| {
+            state.frames += 1;
+            state.frames
+        })
+    }
+
+    /// Applies a function to the accumulators of the identified frame
+    pub(crate) fn accumulate<T>(&self, id: u64, f: impl FnOnce(&mut JsonValue) -> T) -> T {
+        self.with(|state| f(state.accumulating.entry(id).or_default()))
+    }
+
+    /// Publishes the accumulators of a finished frame to the rest of the render
+    pub(crate) fn publish(&self, id: u64) {
+        self.with(|state| {
+            if let Some(JsonValue::Object(accumulators)) = state.accumulating.remove(&id) {
+                for (name, value) in accumulators {
+                    state.accumulators[name] = value;
+                }
+            }
+        })
+    }
+
+    /// The named accumulator most recently published, if any
+    pub(crate) fn published(&self, name: &str) -> Option<JsonValue> {
+        self.with(|state| state.accumulators.get(name).cloned())
+    }
+}
+
+/// The `counter` handler object
+///
+/// It advances a named counter, shared with the `counter` hash parameter of
+/// `repeat`, and writes its new value. To use, register it in your handlebars
+/// registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("counter", Box::new(handlebars_repeat::CounterHelper));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CounterHelper;
+
+impl HelperDef for CounterHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let name = h
+            .param(0)
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("counter", 0))?;
+        let name = name.value().as_str().ok_or_else(|| {
+            RenderErrorReason::ParamTypeMismatchForName(
+                "counter",
+                "0".to_string(),
+                "string".to_string(),
+            )
+        })?;
+
+        let value = Shared::of(h, r, ctx, rc).next(name);
+        out.write(&value.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `unfold` helper
+
+use handlebars::*;
+
+use crate::each;
+use crate::expression::Expression;
+
+/// The `unfold` handler object
+///
+/// `{{#unfold seed=v next="expression" count=N}}` renders its block `count`
+/// times, beginning with the `seed` value. Each following value is the `next`
+/// expression, evaluated within the block context of the repetition before,
+/// where the previous value is the `@value` local variable. For example, with
+/// a `mul` helper, `{{#unfold seed=1 next="mul @value 2" count=4}}` renders
+/// with the values 1, 2, 4 and 8.
+///
+/// Each repetition is rendered with its value as the context, and the block
+/// parameter, if any, is also the value. Besides `@value`, the local variables
+/// are those of `repeat`, such as `@index`, `@first`, `@last` and `@even`, and
+/// the `sep`, `break`, `continue` and `acc` helpers act upon the `unfold`
+/// block. A `count` of zero renders the inverse block.
+///
+/// As for the condition of the `while` helper (see
+/// [`WhileHelper`](crate::WhileHelper)), the `next` expression is a path or a
+/// string holding a path or a subexpression. To use, register it in your
+/// handlebars registry:
+///
+/// ```rust
+/// # use handlebars::*;
+/// let mut reg = Handlebars::new();
+/// reg.register_helper("unfold", Box::new(handlebars_repeat::UnfoldHelper::default()));
+///
+/// handlebars_helper!(mul: |a: Option<u64>, b: u64| a.unwrap_or_default() * b);
+/// reg.register_helper("mul", Box::new(mul));
+///
+/// let template = "{{#unfold seed=1 next=\"mul @value 2\" count=5}}{{this}}s {{/unfold}}";
+/// assert_eq!(reg.render_template(template, &()).unwrap(), "1s 2s 4s 8s 16s ");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UnfoldHelper {
+    max_count: Option<u64>,
+}
+
+impl UnfoldHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+impl HelperDef for UnfoldHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let seed = h
+            .hash_get("seed")
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("unfold", "seed".to_string()))?;
+
+        let count = h.hash_get("count").ok_or_else(|| {
+            RenderErrorReason::ParamNotFoundForName("unfold", "count".to_string())
+        })?;
+        let count = count.value().as_u64().ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "unfold",
+                "count".to_string(),
+                "u64".to_string(),
+            )
+        })?;
+
+        each::limit(count, self.max_count)?;
+
+        let next = h
+            .hash_get("next")
+            .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("unfold", "next".to_string()))?;
+        let next = Expression::new(next).ok_or_else(|| {
+            RenderErrorReason::HashTypeMismatchForName(
+                "unfold",
+                "next".to_string(),
+                "path or expression".to_string(),
+            )
+        })?;
+
+        // Each value after the seed is evaluated within the repetition before.
+        let mut value = Some(seed.value().clone());
+        let items = |rc: &mut RenderContext<'reg, 'rc>| {
+            let value = match value.take() {
+                Some(value) => value,
+                None => next.evaluate(r, ctx, rc, |next| next.clone())?,
+            };
+
+            let mut item = each::Item::new(value.clone());
+            item.locals.set("value", value);
+            Ok(Some(item))
+        };
+
+        each::unfold(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Version 4 UUIDs
+
+use crate::rand::{self, splitmix64};
+
+/// Formats the bits as a version 4 UUID
+fn format(hi: u64, lo: u64) -> String {
+    let hi = (hi & !0xf000) | 0x4000;
+    let lo = (lo & !(0b11 << 62)) | (0b10 << 62);
+
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        hi >> 32,
+        (hi >> 16) & 0xffff,
+        hi & 0xffff,
+        lo >> 48,
+        lo & 0xffff_ffff_ffff
+    )
+}
+
+/// Returns a UUID determined by the seed and index
+pub(crate) fn seeded(seed: u64, index: u64) -> String {
+    let hi = splitmix64(seed ^ splitmix64(index));
+    format(hi, splitmix64(hi))
+}
+
+/// Returns a fresh UUID, from the next two numbers of the generator of the
+/// process
+pub(crate) fn fresh() -> String {
+    let hi = rand::next();
+    format(hi, rand::next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, 0, "00000000-0000-4000-8000-000000000000")]
+    #[case(u64::MAX, u64::MAX, "ffffffff-ffff-4fff-bfff-ffffffffffff")]
+    #[case(
+        0x0123_4567_89ab_cdef,
+        0x0123_4567_89ab_cdef,
+        "01234567-89ab-4def-8123-456789abcdef"
+    )]
+    fn formats(#[case] hi: u64, #[case] lo: u64, #[case] uuid: &str) {
+        assert_eq!(format(hi, lo), uuid);
+    }
+
+    #[test]
+    fn uuids() {
+        assert_eq!(seeded(1, 2), seeded(1, 2));
+        assert_ne!(seeded(1, 2), seeded(1, 3));
+        assert_ne!(seeded(1, 2), seeded(2, 2));
+        assert_ne!(fresh(), fresh());
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `weeks` helper
+
+use handlebars::*;
+
+use crate::date::Date;
+use crate::each;
+
+/// The `weeks` handler object
+///
+/// `{{#weeks year=2025}}` renders its block once for each ISO 8601 week of the
+/// week-numbering year, from Monday to Sunday, so that some weeks begin or end
+/// in the neighbouring years. Alternatively, `{{#weeks from="2025-03-01"
+/// to="2025-03-31"}}` renders it for each week containing any of the dates
+/// from the `from` date up to and including the `to` date. The two forms may
+/// not be combined.
+///
+/// Each repetition is rendered with its week, in the `YYYY-Www` form, as the
+/// context and the block parameter, if any. The `@week` and `@year` local
+/// variables hold the week number and its year, and `@start_date` and
+/// `@end_date` the dates of its Monday and Sunday, formatted by the `format`
+/// hash parameter as for the `dates` helper (see
+/// [`DatesHelper`](crate::DatesHelper)). The `@index`, `@first` and `@last`
+/// local variables are also available. To use, register it in your
+/// handlebars registry:
+///
+/// ```rust
+/// let mut reg = handlebars::Handlebars::new();
+/// reg.register_helper("weeks", Box::new(handlebars_repeat::WeeksHelper::default()));
+///
+/// let template = "{{#weeks year=2025}}{{#if @first}}{{this}}: {{@start_date}} to {{@end_date}}{{/if}}{{/weeks}}";
+/// assert_eq!(
+///     reg.render_template(template, &()).unwrap(),
+///     "2025-W01: 2024-12-30 to 2025-01-05"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WeeksHelper {
+    max_count: Option<u64>,
+}
+
+impl WeeksHelper {
+    /// Sets a maximum number of repetitions, as for the `repeat` helper
+    pub fn with_max_count(mut self, max: u64) -> Self {
+        self.max_count = Some(max);
+        self
+    }
+}
+
+/// Parses the named hash parameter as a date
+fn date(h: &Helper<'_>, name: &'static str) -> Result<Date, RenderError> {
+    let value = h
+        .hash_get(name)
+        .ok_or_else(|| RenderErrorReason::ParamNotFoundForName("weeks", name.to_string()))?;
+
+    value.value().as_str().and_then(Date::parse).ok_or_else(|| {
+        RenderErrorReason::HashTypeMismatchForName("weeks", name.to_string(), "date".to_string())
+            .into()
+    })
+}
+
+impl HelperDef for WeeksHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let (start, count) = match h.hash_get("year") {
+            Some(..) if h.hash_get("from").is_some() || h.hash_get("to").is_some() => {
+                return Err(RenderErrorReason::Other(
+                    "weeks helper given both year and from or to".to_string(),
+                )
+                .into())
+            }
+
+            Some(year) => {
+                let year = year
+                    .value()
+                    .as_i64()
+                    .filter(|year| (1..=9999).contains(year))
+                    .ok_or_else(|| {
+                        RenderErrorReason::HashTypeMismatchForName(
+                            "weeks",
+                            "year".to_string(),
+                            "year".to_string(),
+                        )
+                    })?;
+
+                let start = Date::first_week(year);
+                let end = Date::first_week(year + 1);
+                (start, ((end.days() - start.days()) / 7) as u64)
+            }
+
+            None => {
+                let from = date(h, "from")?;
+                let to = date(h, "to")?;
+                let start = from.add_days(-i64::from(from.weekday()));
+                match to < from {
+                    true => (start, 0),
+                    false => (start, ((to.days() - start.days()) / 7) as u64 + 1),
+                }
+            }
+        };
+
+        let format = match h.hash_get("format") {
+            None => "%Y-%m-%d",
+            Some(format) => match format.value().as_str() {
+                Some(format) if start.format(format).is_some() => format,
+                _ => {
+                    return Err(RenderErrorReason::HashTypeMismatchForName(
+                        "weeks",
+                        "format".to_string(),
+                        "strftime-style format".to_string(),
+                    )
+                    .into())
+                }
+            },
+        };
+
+        let items = (0..count).map(|n| {
+            let monday = start.add_days(n as i64 * 7);
+            let sunday = monday.add_days(6);
+            let (year, week) = monday.iso_week();
+
+            let mut item = each::Item::new(format!("{:04}-W{:02}", year, week));
+            item.locals.set("week", week);
+            item.locals.set("year", year);
+            item.locals
+                .set("start_date", monday.format(format).unwrap_or_default());
+            item.locals
+                .set("end_date", sunday.format(format).unwrap_or_default());
+            item
+        });
+
+        each::render(self.max_count, count, items, h, r, ctx, rc, out)
+    }
+}